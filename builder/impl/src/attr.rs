@@ -0,0 +1,423 @@
+use proc_macro2::{Group, Span, TokenStream};
+use quote::{quote, ToTokens};
+use std::collections::BTreeMap;
+use syn::meta::ParseNestedMeta;
+use syn::spanned::Spanned;
+use syn::{token, Attribute, Expr, Ident, LitBool, LitStr, Path, Token, Type, Visibility};
+
+/// Options given through `#[builder(...)]` on the struct itself.
+#[derive(Default)]
+pub struct StructAttrs {
+    /// Track which required fields have been set in the builder's type so that
+    /// `build()` only exists once all of them are.
+    pub typestate: bool,
+    /// Function checking the assembled struct before `build()` returns it.
+    pub validate: Option<Path>,
+    /// `#[builder(validate_error = "...")]`: the error type of the `validate`
+    /// function, carried as is rather than as its message.
+    pub validate_error: Option<Type>,
+    /// `#[builder(setter(into))]` applied to every field.
+    pub setter_into: bool,
+    /// How setters and `build()` receive the builder.
+    pub pattern: Pattern,
+    pub pattern_span: Option<Span>,
+    /// Derive `serde::Deserialize` for the builder.
+    pub deserialize: bool,
+    /// Refer to `core` and `alloc` rather than `std`.
+    pub no_std: bool,
+    /// Make `build()` an `async fn`, which is needed for `default_async`.
+    pub async_build: bool,
+    /// `#[builder(const)]`: the builder can be used in `const` context.
+    pub constant: bool,
+    /// Where each key was given, for errors about keys that conflict.
+    spans: BTreeMap<&'static str, Span>,
+}
+
+/// The receiver of the builder's setters and `build()`.
+#[derive(Clone, Copy, PartialEq, Default)]
+pub enum Pattern {
+    /// `&mut self`, as in `std::process::Command`; `build()` clones.
+    #[default]
+    Mutable,
+    /// `self`; `build()` moves the fields out of the builder.
+    Owned,
+    /// `&self`; setters return a modified clone and `build()` clones.
+    Immutable,
+}
+#[derive(Default)]
+pub struct FieldAttrs {
+    /// Name of the one-element-at-a-time setter for a `Vec` field.
+    pub each: Option<Ident>,
+    /// `each(item = "...")`, the type that setter takes for a collection
+    /// whose element type cannot be told from how it is written.
+    pub each_item: Option<Type>,
+    /// Expression used by `build()` when the field was never set, from
+    /// `#[builder(default)]` or `#[builder(default = "...")]`.
+    pub default: Option<TokenStream>,
+    /// Whether `default` was given without an expression, and so calls
+    /// `Default::default()`.
+    pub default_trait: bool,
+    /// `#[builder(default_with = "...")]`, a function returning a `Result`.
+    pub default_with: Option<Path>,
+    /// `#[builder(default_async = "...")]`, an async function returning a
+    /// `Result`.
+    pub default_async: Option<Path>,
+    /// Setters accept `impl Into<T>` rather than `T`.
+    pub setter_into: bool,
+    /// `setter(name = "...")`, renaming the setter.
+    pub setter_name: Option<Ident>,
+    /// `setter(skip)`: the field is only ever set from its default.
+    pub setter_skip: bool,
+    /// `setter(strip_option = false)`: the setter of an `Option<T>` field
+    /// takes the `Option<T>` itself.
+    pub strip_option: Option<LitBool>,
+    /// Visibility of the field's setters, `pub` unless given.
+    pub vis: Option<Visibility>,
+    /// Name of a tuple struct field's setter in place of `_0`, `_1`, ...
+    pub name: Option<Ident>,
+    /// The field's type derives `Builder` too, and is filled in through its
+    /// builder rather than set as a whole.
+    pub sub_builder: bool,
+    /// Environment variable `from_env()` parses the field from.
+    pub env: Option<LitStr>,
+}
+
+impl StructAttrs {
+    pub fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut out = StructAttrs::default();
+        let mut errors = Errors::default();
+        for attr in builder_attrs(attrs) {
+            let parsed = attr.parse_nested_meta(|meta| {
+                if let Some(key) = STRUCT_KEYS.iter().find(|key| meta.path.is_ident(key)) {
+                    out.spans.insert(key, meta.path.span());
+                }
+                if meta.path.is_ident("typestate") {
+                    out.typestate = true;
+                } else if meta.path.is_ident("validate") {
+                    let path: LitStr = meta.value()?.parse()?;
+                    out.validate = Some(path.parse()?);
+                } else if meta.path.is_ident("validate_error") {
+                    let ty: LitStr = meta.value()?.parse()?;
+                    out.validate_error = Some(ty.parse()?);
+                } else if meta.path.is_ident("setter") {
+                    parse_setter(&meta, &mut out.setter_into, &mut errors)?;
+                } else if meta.path.is_ident("pattern") {
+                    let pattern: LitStr = meta.value()?.parse()?;
+                    out.pattern = match pattern.value().as_str() {
+                        "mutable" => Pattern::Mutable,
+                        "owned" => Pattern::Owned,
+                        "immutable" => Pattern::Immutable,
+                        _ => {
+                            return Err(syn::Error::new(
+                                pattern.span(),
+                                "expected `\"mutable\"`, `\"owned\"` or `\"immutable\"`",
+                            ))
+                        }
+                    };
+                    out.pattern_span = Some(pattern.span());
+                } else if meta.path.is_ident("deserialize") {
+                    if !cfg!(feature = "serde") {
+                        return Err(meta.error(
+                            "`deserialize` requires the `serde` feature of derive_builder",
+                        ));
+                    }
+                    out.deserialize = true;
+                } else if meta.path.is_ident("no_std") {
+                    out.no_std = true;
+                } else if meta.path.is_ident("async_build") {
+                    out.async_build = true;
+                } else if meta.path.is_ident("const") {
+                    out.constant = true;
+                } else {
+                    errors.push(unknown(&meta, "`builder` attribute", STRUCT_KEYS)?);
+                }
+                Ok(())
+            });
+            if let Err(err) = parsed {
+                errors.push(err);
+            }
+        }
+
+        if let (Some(ty), None) = (&out.validate_error, &out.validate) {
+            errors.push(syn::Error::new_spanned(
+                ty,
+                "`validate_error` requires `validate`",
+            ));
+        }
+        if out.constant {
+            let unsupported = [
+                (out.typestate, "typestate", "typestate"),
+                (out.validate.is_some(), "validate", "validate"),
+                (out.async_build, "async_build", "async_build"),
+                (out.setter_into, "setter", "setter(into)"),
+                (out.deserialize, "deserialize", "deserialize"),
+            ];
+            for (_, key, option) in unsupported.iter().filter(|(given, ..)| *given) {
+                errors.push(syn::Error::new(
+                    out.span(key),
+                    format!("const builders do not support `{}`", option),
+                ));
+            }
+        }
+        if let Some(span) = out.pattern_span {
+            if out.constant && out.pattern != Pattern::Owned {
+                errors.push(syn::Error::new(
+                    span,
+                    "const builders always use the owned pattern",
+                ));
+            }
+        }
+        if out.typestate && out.async_build {
+            errors.push(syn::Error::new(
+                out.span("async_build"),
+                "typestate builders cannot be built asynchronously",
+            ));
+        }
+        if out.typestate && out.deserialize {
+            errors.push(syn::Error::new(
+                out.span("deserialize"),
+                "typestate builders cannot be deserialized",
+            ));
+        }
+        if let Some(span) = out.pattern_span {
+            if out.typestate && out.pattern != Pattern::Owned {
+                errors.push(syn::Error::new(
+                    span,
+                    "typestate builders always use the owned pattern",
+                ));
+            }
+        }
+        errors.finish()?;
+        Ok(out)
+    }
+
+    /// Where `key` was given, which it must have been.
+    fn span(&self, key: &str) -> Span {
+        self.spans[key]
+    }
+}
+
+impl FieldAttrs {
+    pub fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut out = FieldAttrs::default();
+        let mut errors = Errors::default();
+        for attr in builder_attrs(attrs) {
+            let parsed = attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("each") {
+                    if meta.input.peek(token::Paren) {
+                        out.parse_each(&meta, &mut errors)?;
+                    } else {
+                        let name: LitStr = meta.value()?.parse()?;
+                        out.each = Some(name.parse()?);
+                    }
+                } else if meta.path.is_ident("default") {
+                    out.default_trait = !meta.input.peek(Token![=]);
+                    out.default = Some(if out.default_trait {
+                        quote!(::std::default::Default::default())
+                    } else {
+                        let expr: LitStr = meta.value()?.parse()?;
+                        let expr: Expr = expr.parse()?;
+                        quote!(#expr)
+                    });
+                } else if meta.path.is_ident("default_with") {
+                    let path: LitStr = meta.value()?.parse()?;
+                    out.default_with = Some(path.parse()?);
+                } else if meta.path.is_ident("default_async") {
+                    let path: LitStr = meta.value()?.parse()?;
+                    out.default_async = Some(path.parse()?);
+                } else if meta.path.is_ident("setter") {
+                    out.parse_setter(&meta, &mut errors)?;
+                } else if meta.path.is_ident("vis") {
+                    let vis: LitStr = meta.value()?.parse()?;
+                    out.vis = Some(vis.parse()?);
+                } else if meta.path.is_ident("name") {
+                    let name: LitStr = meta.value()?.parse()?;
+                    out.name = Some(name.parse()?);
+                } else if meta.path.is_ident("sub_builder") {
+                    out.sub_builder = true;
+                } else if meta.path.is_ident("env") {
+                    out.env = Some(meta.value()?.parse()?);
+                } else {
+                    errors.push(unknown(&meta, "`builder` attribute", FIELD_KEYS)?);
+                }
+                Ok(())
+            });
+            if let Err(err) = parsed {
+                errors.push(err);
+            }
+        }
+        errors.finish()?;
+        Ok(out)
+    }
+
+    /// Parses the contents of `each(...)`, the long form of `each = "..."`.
+    fn parse_each(&mut self, meta: &ParseNestedMeta, errors: &mut Errors) -> syn::Result<()> {
+        meta.parse_nested_meta(|nested| {
+            if nested.path.is_ident("name") {
+                let name: LitStr = nested.value()?.parse()?;
+                self.each = Some(name.parse()?);
+            } else if nested.path.is_ident("item") {
+                let item: LitStr = nested.value()?.parse()?;
+                self.each_item = Some(item.parse()?);
+            } else {
+                errors.push(unknown(&nested, "`each` option", FIELD_EACH_KEYS)?);
+            }
+            Ok(())
+        })?;
+        if self.each.is_none() {
+            errors.push(meta.error("expected `each(name = \"...\")`"));
+        }
+        Ok(())
+    }
+
+    /// Parses the contents of `setter(...)` on a field.
+    fn parse_setter(&mut self, meta: &ParseNestedMeta, errors: &mut Errors) -> syn::Result<()> {
+        meta.parse_nested_meta(|nested| {
+            if nested.path.is_ident("into") {
+                self.setter_into = true;
+            } else if nested.path.is_ident("name") {
+                let name: LitStr = nested.value()?.parse()?;
+                self.setter_name = Some(name.parse()?);
+            } else if nested.path.is_ident("skip") {
+                self.setter_skip = true;
+            } else if nested.path.is_ident("strip_option") {
+                self.strip_option = Some(if nested.input.peek(Token![=]) {
+                    nested.value()?.parse()?
+                } else {
+                    LitBool::new(true, nested.path.span())
+                });
+            } else {
+                errors.push(unknown(&nested, "`setter` option", FIELD_SETTER_KEYS)?);
+            }
+            Ok(())
+        })
+    }
+}
+
+/// Parses the contents of `setter(...)` on the struct.
+fn parse_setter(meta: &ParseNestedMeta, into: &mut bool, errors: &mut Errors) -> syn::Result<()> {
+    meta.parse_nested_meta(|nested| {
+        if nested.path.is_ident("into") {
+            *into = true;
+        } else {
+            errors.push(unknown(&nested, "`setter` option", STRUCT_SETTER_KEYS)?);
+        }
+        Ok(())
+    })
+}
+
+const STRUCT_KEYS: &[&str] = &[
+    "typestate",
+    "validate",
+    "validate_error",
+    "setter",
+    "pattern",
+    "deserialize",
+    "no_std",
+    "async_build",
+    "const",
+];
+const STRUCT_SETTER_KEYS: &[&str] = &["into"];
+const FIELD_KEYS: &[&str] = &[
+    "each",
+    "default",
+    "default_with",
+    "default_async",
+    "setter",
+    "vis",
+    "name",
+    "sub_builder",
+    "env",
+];
+const FIELD_EACH_KEYS: &[&str] = &["name", "item"];
+const FIELD_SETTER_KEYS: &[&str] = &["into", "name", "skip", "strip_option"];
+
+/// Errors collected over all attributes, so that they are reported in one go
+/// rather than one per compile.
+#[derive(Default)]
+pub struct Errors(Option<syn::Error>);
+
+impl Errors {
+    pub fn push(&mut self, err: syn::Error) {
+        match &mut self.0 {
+            Some(errors) => errors.combine(err),
+            None => self.0 = Some(err),
+        }
+    }
+
+    /// Keeps the value of `result`, recording its error if there is one.
+    pub fn ok<T>(&mut self, result: syn::Result<T>) -> Option<T> {
+        result.map_err(|err| self.push(err)).ok()
+    }
+
+    pub fn finish(self) -> syn::Result<()> {
+        match self.0 {
+            Some(errors) => Err(errors),
+            None => Ok(()),
+        }
+    }
+}
+
+/// The error for the key of `meta`, which is none of `known`. Its value, if
+/// any, is skipped so that parsing can go on with the keys after it.
+fn unknown(meta: &ParseNestedMeta, what: &str, known: &[&str]) -> syn::Result<syn::Error> {
+    if meta.input.peek(Token![=]) {
+        meta.value()?.parse::<Expr>()?;
+    } else if meta.input.peek(token::Paren) {
+        meta.input.parse::<Group>()?;
+    }
+
+    let key = meta.path.to_token_stream().to_string().replace(' ', "");
+    let msg = match closest(&key, known) {
+        Some(suggestion) => format!("unknown {} `{}`, did you mean `{}`?", what, key, suggestion),
+        None => {
+            let mut expected = known.iter().map(|key| format!("`{}`", key));
+            let last = expected.next_back().unwrap();
+            let expected = expected.collect::<Vec<_>>();
+            if expected.is_empty() {
+                format!("unknown {} `{}`, expected {}", what, key, last)
+            } else {
+                format!(
+                    "unknown {} `{}`, expected one of {} or {}",
+                    what,
+                    key,
+                    expected.join(", "),
+                    last,
+                )
+            }
+        }
+    };
+    Ok(syn::Error::new_spanned(&meta.path, msg))
+}
+
+/// The entry of `known` most likely meant by the misspelled `key`, if any is
+/// close enough.
+fn closest<'a>(key: &str, known: &[&'a str]) -> Option<&'a str> {
+    let max = std::cmp::max(key.len(), 3) / 3;
+    known
+        .iter()
+        .map(|candidate| (edit_distance(key, candidate), *candidate))
+        .filter(|(distance, _)| *distance <= max)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// Levenshtein distance between `a` and `b`.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut row = (0..=b.len()).collect::<Vec<_>>();
+    for (i, a) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, b) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(a != *b);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(row[j + 1] + 1);
+        }
+    }
+    row[b.len()]
+}
+
+fn builder_attrs(attrs: &[Attribute]) -> impl Iterator<Item = &Attribute> {
+    attrs.iter().filter(|attr| attr.path().is_ident("builder"))
+}
@@ -0,0 +1,147 @@
+//! Expansion for `#[builder(const)]`.
+//!
+//! A `const fn` cannot call trait methods, which rules out cloning the
+//! builder, `Into` conversions, `Default::default()` and growing collections.
+//! The builder is therefore always taken by value, fields are only ever set as
+//! a whole, and `build()` returns the value directly and panics if a required
+//! field is missing. In a `const` or `static` initializer, that panic is a
+//! compile error. `BuilderOps::build()` is not `const`, and reports a missing
+//! field through the usual error enum instead.
+
+use crate::error::BuilderError;
+use crate::field::{DefaultValue, Kind};
+use crate::target::Target;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::ext::IdentExt;
+
+pub fn expand(target: &Target) -> TokenStream {
+    let vis = target.vis;
+    let ty = target.ty;
+    let ctor = &target.ctor;
+    let builder_fn = &target.builder_fn;
+    let builder = &target.builder;
+    let fields = &target.fields;
+    let phantom_field = target.phantom_field();
+    let phantom_init = target.phantom_init();
+    let builder_doc = target.builder_doc();
+    let builder_fn_doc = target.builder_fn_doc();
+    let build_doc = format!(
+        " Builds [`{}`], panicking if a required field is not set.",
+        target.doc_name(),
+    );
+
+    let generics = target.generics;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let idents = fields.iter().map(|f| &f.ident).collect::<Vec<_>>();
+    let members = fields.iter().map(|f| &f.member);
+    let storage = fields.iter().map(|f| f.storage_ty());
+    let setters = fields.iter().filter_map(|field| {
+        let ident = &field.ident;
+        let vis = &field.vis;
+        let setter = field.setter.as_ref()?;
+        let ty = field.storage_ty();
+        let is_set = format_ident!("is_{}_set", ident);
+        let doc = field.setter_doc(&format!("Sets `{}`.", ident.unraw()));
+        let is_set_doc = format!(" Whether `{}` has been set.", ident.unraw());
+        Some(quote! {
+            #doc
+            #vis const fn #setter(mut self, #ident: #ty) -> Self {
+                self.#ident = ::std::option::Option::Some(#ident);
+                self
+            }
+
+            #[doc = #is_set_doc]
+            #vis const fn #is_set(&self) -> bool {
+                ::std::option::Option::is_some(&self.#ident)
+            }
+        })
+    });
+    let inits = fields.iter().map(|field| {
+        let ident = &field.ident;
+        let present = match field.kind {
+            Kind::Optional(_) => quote!(::std::option::Option::Some(value)),
+            _ => quote!(value),
+        };
+        let fallback = match (&field.default, &field.kind) {
+            (Some(DefaultValue::Expr(default)), _) => default.clone(),
+            (_, Kind::Optional(_)) => quote!(::std::option::Option::None),
+            _ => {
+                let msg = format!("field `{}` is not set", ident.unraw());
+                quote!(::std::panic!(#msg))
+            }
+        };
+        quote! {
+            match self.#ident {
+                ::std::option::Option::Some(value) => #present,
+                ::std::option::Option::None => #fallback,
+            }
+        }
+    });
+    let impl_from = target.impl_from(quote!(#builder #ty_generics), phantom_init.clone());
+    let error = BuilderError::new(target);
+    let error_ident = &error.ident;
+    let error_enum = error.expand();
+    let checked_members = fields.iter().map(|f| &f.member);
+    let checked_inits = fields.iter().map(|f| {
+        let ident = &f.ident;
+        f.build_expr(quote!(self.#ident), &error)
+    });
+
+    quote! {
+        impl #impl_generics #ty #ty_generics #where_clause {
+            #builder_fn_doc
+            pub const fn #builder_fn() -> #builder #ty_generics {
+                #builder::new()
+            }
+        }
+
+        #builder_doc
+        #vis struct #builder #generics #where_clause {
+            #(#idents: ::std::option::Option<#storage>,)*
+            #phantom_field
+        }
+
+        impl #impl_generics #builder #ty_generics #where_clause {
+            /// Creates an empty builder, like `Default::default()` but
+            /// usable in `const` context.
+            pub const fn new() -> Self {
+                #builder {
+                    #(#idents: ::std::option::Option::None,)*
+                    #phantom_init
+                }
+            }
+
+            #(#setters)*
+
+            #[doc = #build_doc]
+            pub const fn build(self) -> #ty #ty_generics {
+                #ctor {
+                    #(#members: #inits,)*
+                }
+            }
+        }
+
+        impl #impl_generics ::std::default::Default for #builder #ty_generics #where_clause {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+
+        #impl_from
+
+        #error_enum
+
+        impl #impl_generics ::derive_builder::BuilderOps for #builder #ty_generics #where_clause {
+            type Target = #ty #ty_generics;
+            type Error = #error_ident;
+
+            fn build(self) -> ::std::result::Result<#ty #ty_generics, #error_ident> {
+                ::std::result::Result::Ok(#ctor {
+                    #(#checked_members: #checked_inits,)*
+                })
+            }
+        }
+    }
+}
@@ -0,0 +1,55 @@
+//! `from_env()`, which starts a builder from the fields marked
+//! `#[builder(env = "...")]`.
+
+use crate::error::BuilderError;
+use crate::target::Target;
+use proc_macro2::TokenStream;
+use quote::quote;
+
+pub fn expand(target: &Target, error: &BuilderError) -> TokenStream {
+    let fields = target
+        .fields
+        .iter()
+        .filter(|f| f.env.is_some())
+        .collect::<Vec<_>>();
+    if fields.is_empty() {
+        return TokenStream::new();
+    }
+
+    let ty = target.ty;
+    let builder_fn = &target.builder_fn;
+    let error_ident = &error.ident;
+    // A variable that is not set leaves its field unset, as if its setter had
+    // not been called.
+    let reads = fields.iter().map(|f| {
+        let ident = &f.ident;
+        let var = f.env.as_ref().unwrap();
+        let storage = f.storage_ty();
+        let invalid = error.invalid_env(var, quote!(err));
+        quote! {
+            match ::std::env::var(#var) {
+                ::std::result::Result::Ok(value) => {
+                    match <#storage as ::std::str::FromStr>::from_str(&value) {
+                        ::std::result::Result::Ok(value) => {
+                            builder.#ident = ::std::option::Option::Some(value);
+                        }
+                        ::std::result::Result::Err(err) => #invalid,
+                    }
+                }
+                ::std::result::Result::Err(::std::env::VarError::NotPresent) => {}
+                ::std::result::Result::Err(err) => #invalid,
+            }
+        }
+    });
+
+    quote! {
+        /// Creates a builder with the fields marked `env` set from their
+        /// environment variables, leaving those whose variable is not set
+        /// unset.
+        pub fn from_env() -> ::std::result::Result<Self, #error_ident> {
+            let mut builder = #ty::#builder_fn();
+            #(#reads)*
+            ::std::result::Result::Ok(builder)
+        }
+    }
+}
@@ -0,0 +1,482 @@
+//! The `...BuilderError` enum returned from a fallible `build()`.
+
+use crate::attr::{Errors, StructAttrs};
+use crate::field::{camel_case, DefaultValue, Field, Kind};
+use crate::target::Target;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote, ToTokens};
+use std::collections::BTreeMap;
+use syn::ext::IdentExt;
+use syn::{Ident, LitStr, Path, Type, Visibility};
+
+pub struct BuilderError<'a> {
+    pub ident: Ident,
+    builder: &'a Ident,
+    vis: &'a Visibility,
+    missing: Vec<(&'a Ident, Ident)>,
+    /// Sub-builder fields, with the variant wrapping their builder's error.
+    nested: Vec<(&'a Ident, Ident, &'a Path)>,
+    validate: Option<&'a Path>,
+    /// The error type of `validate`, if carried as is.
+    validate_error: Option<&'a Type>,
+    /// Whether `from_env()` exists and can fail.
+    env: bool,
+    /// Whether a field has a `default_with` or `default_async` function.
+    default_failed: bool,
+}
+
+impl<'a> BuilderError<'a> {
+    pub fn new(target: &'a Target) -> Self {
+        // A typestate builder cannot be built with a field missing.
+        let mut missing = Vec::new();
+        let mut nested = Vec::new();
+        for f in &target.fields {
+            let Some(variant) = field_variant(f, target.attrs) else {
+                continue;
+            };
+            match &f.kind {
+                Kind::Nested { error, .. } => nested.push((&f.ident, variant, &**error)),
+                _ => missing.push((&f.ident, variant)),
+            }
+        }
+
+        BuilderError {
+            ident: format_ident!("{}Error", target.builder),
+            builder: &target.builder,
+            vis: target.vis,
+            missing,
+            nested,
+            validate: target.attrs.validate.as_ref(),
+            validate_error: target.attrs.validate_error.as_ref(),
+            env: target.fields.iter().any(|f| f.env.is_some()),
+            default_failed: target.fields.iter().any(|f| {
+                matches!(
+                    f.default,
+                    Some(DefaultValue::With(_) | DefaultValue::Async(_))
+                )
+            }),
+        }
+    }
+
+    /// Whether `build()` can fail at all.
+    pub fn is_fallible(&self) -> bool {
+        !self.missing.is_empty()
+            || !self.nested.is_empty()
+            || self.validate.is_some()
+            || self.default_failed
+    }
+
+    /// Whether `build()` can only fail because a required field is not set,
+    /// provided that the same holds for every sub-builder.
+    pub fn only_missing(&self) -> bool {
+        self.validate.is_none() && !self.default_failed
+    }
+
+    /// Expression diverging because the required `field` was never set.
+    pub fn missing(&self, field: &Ident) -> TokenStream {
+        if !self.missing.iter().any(|(missing, _)| *missing == field) {
+            return quote!(::std::unreachable!());
+        }
+        let ident = &self.ident;
+        let variant = missing_variant(field);
+        quote!(return ::std::result::Result::Err(#ident::#variant))
+    }
+
+    /// Path of the variant wrapping the error of the sub-builder for `field`.
+    pub fn nested(&self, field: &Ident) -> TokenStream {
+        let ident = &self.ident;
+        let variant = nested_variant(field);
+        quote!(#ident::#variant)
+    }
+
+    /// Statements running the `validate` hook, if any, on the already
+    /// assembled `value` and returning early if it fails.
+    pub fn validate(&self, value: &Ident) -> TokenStream {
+        let Some(validate) = self.validate else {
+            return TokenStream::new();
+        };
+        let ident = &self.ident;
+        let err = if self.validate_error.is_some() {
+            quote!(::std::convert::From::from(err))
+        } else {
+            quote!(::std::string::ToString::to_string(&err))
+        };
+        quote! {
+            if let ::std::result::Result::Err(err) = #validate(&#value) {
+                return ::std::result::Result::Err(#ident::Validation(#err));
+            }
+        }
+    }
+
+    /// Expression returning early because the environment variable `var`
+    /// could not be read or parsed, as described by the error `err`.
+    pub fn invalid_env(&self, var: &LitStr, err: TokenStream) -> TokenStream {
+        let ident = &self.ident;
+        quote! {
+            return ::std::result::Result::Err(#ident::InvalidEnv {
+                var: #var,
+                message: ::std::string::ToString::to_string(&#err),
+            })
+        }
+    }
+
+    /// Expression returning early because the function computing the default
+    /// of `field` failed with the error `err`.
+    pub fn default_failed(&self, field: &Ident, err: TokenStream) -> TokenStream {
+        let ident = &self.ident;
+        let field = field.unraw().to_string();
+        quote! {
+            return ::std::result::Result::Err(#ident::DefaultFailed {
+                field: #field,
+                message: ::std::string::ToString::to_string(&#err),
+            })
+        }
+    }
+
+    /// `impl From<...BuilderError> for derive_builder::MissingFields`, if
+    /// every variant is for a missing field, possibly of a sub-builder.
+    pub fn impl_missing_fields(&self) -> TokenStream {
+        if !self.only_missing() || self.env {
+            return TokenStream::new();
+        }
+        let ident = &self.ident;
+        let missing = self.missing.iter().map(|(field, variant)| {
+            let name = field.unraw().to_string();
+            quote! {
+                #ident::#variant => ::derive_builder::MissingFields::new(
+                    ::std::vec![#name],
+                    ::std::vec::Vec::new(),
+                ),
+            }
+        });
+        let nested = self.nested.iter().map(|(field, variant, _)| {
+            let name = field.unraw().to_string();
+            quote! {
+                #ident::#variant(err) => ::derive_builder::MissingFields::new(
+                    ::std::vec![#name],
+                    ::std::vec![(#name, ::std::convert::From::from(err))],
+                ),
+            }
+        });
+        // A sub-builder's error may not convert, in which case this one does
+        // not either. `for<'__builder>` defers the bound to where the impl is used.
+        let errors = self.nested.iter().map(|(_, _, error)| error);
+        quote! {
+            impl ::std::convert::From<#ident> for ::derive_builder::MissingFields
+            where
+                #(for<'__builder> ::derive_builder::MissingFields: ::std::convert::From<#errors>,)*
+            {
+                fn from(err: #ident) -> Self {
+                    match err {
+                        #(#missing)*
+                        #(#nested)*
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn expand(&self) -> TokenStream {
+        let vis = self.vis;
+        let ident = &self.ident;
+        let mut variants = Vec::new();
+        let mut display = Vec::new();
+        let mut shapes = Vec::new();
+        for (field, variant) in &self.missing {
+            let doc = format!(" The required field `{}` was not set.", field.unraw());
+            let msg = format!("field `{}` is not set", field.unraw());
+            variants.push(quote! {
+                #[doc = #doc]
+                #variant
+            });
+            display.push(quote! {
+                #ident::#variant => f.write_str(#msg),
+            });
+            shapes.push((variant.clone(), Shape::Unit));
+        }
+        for (field, variant, error) in &self.nested {
+            let msg = format!("in `{}`: {{}}", field.unraw());
+            let doc = format!(
+                " Building `{}` through its own builder failed.",
+                field.unraw()
+            );
+            variants.push(quote! {
+                #[doc = #doc]
+                #variant(#error)
+            });
+            display.push(quote! {
+                #ident::#variant(ref err) => ::std::write!(f, #msg, err),
+            });
+            shapes.push((variant.clone(), Shape::Tuple));
+        }
+        let sources = self.nested.iter().map(|(_, variant, _)| {
+            quote! {
+                #ident::#variant(ref err) => ::std::option::Option::Some(err),
+            }
+        });
+        if self.validate.is_some() {
+            variants.push(match self.validate_error {
+                Some(ty) => quote! {
+                    /// The `validate` function rejected the value with this
+                    /// error.
+                    Validation(#ty)
+                },
+                None => quote! {
+                    /// The `validate` function rejected the value, with this
+                    /// message.
+                    Validation(::std::string::String)
+                },
+            });
+            display.push(quote! {
+                #ident::Validation(ref err) => ::std::fmt::Display::fmt(err, f),
+            });
+            shapes.push((format_ident!("Validation"), Shape::Tuple));
+        }
+        if self.env {
+            variants.push(quote! {
+                /// An environment variable read by `from_env()` could not be
+                /// parsed.
+                InvalidEnv {
+                    /// Name of the variable.
+                    var: &'static str,
+                    /// Why reading or parsing it failed.
+                    message: ::std::string::String,
+                }
+            });
+            display.push(quote! {
+                #ident::InvalidEnv { var, ref message } => {
+                    ::std::write!(f, "environment variable `{}`: {}", var, message)
+                }
+            });
+            shapes.push((
+                format_ident!("InvalidEnv"),
+                Shape::Named(&["var", "message"]),
+            ));
+        }
+        if self.default_failed {
+            variants.push(quote! {
+                /// The function computing the default of a field that was not
+                /// set failed.
+                DefaultFailed {
+                    /// Name of the field.
+                    field: &'static str,
+                    /// The error returned by the function.
+                    message: ::std::string::String,
+                }
+            });
+            display.push(quote! {
+                #ident::DefaultFailed { field, ref message } => {
+                    ::std::write!(f, "default of `{}` failed: {}", field, message)
+                }
+            });
+            shapes.push((
+                format_ident!("DefaultFailed"),
+                Shape::Named(&["field", "message"]),
+            ));
+        }
+
+        // The error type of `validate` may not be `Clone` or `PartialEq`, nor
+        // may that of a sub-builder, which can carry one of its own. The
+        // builder's error is not either then. `for<'__builder>` defers the
+        // bounds to where the impls are used; they would be errors right away
+        // otherwise.
+        let carried = self
+            .validate_error
+            .map(ToTokens::to_token_stream)
+            .into_iter()
+            .chain(
+                self.nested
+                    .iter()
+                    .map(|(_, _, error)| error.to_token_stream()),
+            )
+            .collect::<Vec<_>>();
+        let (derives, comparisons) = match carried.is_empty() {
+            true => (
+                quote! {
+                    #[derive(
+                        ::std::fmt::Debug,
+                        ::std::clone::Clone,
+                        ::std::cmp::PartialEq,
+                        ::std::cmp::Eq,
+                    )]
+                },
+                TokenStream::new(),
+            ),
+            false => {
+                let clones = shapes
+                    .iter()
+                    .map(|(variant, shape)| shape.clone_arm(ident, variant));
+                let eqs = shapes
+                    .iter()
+                    .map(|(variant, shape)| shape.eq_arm(ident, variant));
+                (
+                    quote!(#[derive(::std::fmt::Debug)]),
+                    quote! {
+                        impl ::std::clone::Clone for #ident
+                        where
+                            #(for<'__builder> #carried: ::std::clone::Clone,)*
+                        {
+                            fn clone(&self) -> Self {
+                                match *self {
+                                    #(#clones)*
+                                }
+                            }
+                        }
+
+                        impl ::std::cmp::PartialEq for #ident
+                        where
+                            #(for<'__builder> #carried: ::std::cmp::PartialEq,)*
+                        {
+                            fn eq(&self, other: &Self) -> bool {
+                                #[allow(unreachable_patterns)]
+                                match (self, other) {
+                                    #(#eqs)*
+                                    _ => false,
+                                }
+                            }
+                        }
+
+                        impl ::std::cmp::Eq for #ident
+                        where
+                            #(for<'__builder> #carried: ::std::cmp::Eq,)*
+                        {
+                        }
+                    },
+                )
+            }
+        };
+
+        let doc = format!(" Error returned by [`{}::build()`].", self.builder);
+        quote! {
+            #[doc = #doc]
+            #derives
+            #vis enum #ident {
+                #(#variants,)*
+            }
+
+            #comparisons
+
+            impl ::std::fmt::Display for #ident {
+                fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                    match *self {
+                        #(#display)*
+                    }
+                }
+            }
+
+            impl ::std::error::Error for #ident {
+                fn source(&self) -> ::std::option::Option<&(dyn ::std::error::Error + 'static)> {
+                    #[allow(unreachable_patterns)]
+                    match *self {
+                        #(#sources)*
+                        _ => ::std::option::Option::None,
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The fields of a variant of the error enum, for the impls that cannot be
+/// derived.
+enum Shape {
+    Unit,
+    /// A single unnamed field.
+    Tuple,
+    Named(&'static [&'static str]),
+}
+
+impl Shape {
+    fn clone_arm(&self, ident: &Ident, variant: &Ident) -> TokenStream {
+        match self {
+            Shape::Unit => quote!(#ident::#variant => #ident::#variant,),
+            Shape::Tuple => quote! {
+                #ident::#variant(ref err) => #ident::#variant(::std::clone::Clone::clone(err)),
+            },
+            Shape::Named(fields) => {
+                let fields = fields
+                    .iter()
+                    .map(|f| format_ident!("{}", f))
+                    .collect::<Vec<_>>();
+                quote! {
+                    #ident::#variant { #(ref #fields),* } => #ident::#variant {
+                        #(#fields: ::std::clone::Clone::clone(#fields),)*
+                    },
+                }
+            }
+        }
+    }
+
+    fn eq_arm(&self, ident: &Ident, variant: &Ident) -> TokenStream {
+        match self {
+            Shape::Unit => quote!((#ident::#variant, #ident::#variant) => true,),
+            Shape::Tuple => quote! {
+                (#ident::#variant(a), #ident::#variant(b)) => a == b,
+            },
+            Shape::Named(fields) => {
+                let fields = fields
+                    .iter()
+                    .map(|f| format_ident!("{}", f))
+                    .collect::<Vec<_>>();
+                let a = fields
+                    .iter()
+                    .map(|f| format_ident!("a_{}", f))
+                    .collect::<Vec<_>>();
+                let b = fields
+                    .iter()
+                    .map(|f| format_ident!("b_{}", f))
+                    .collect::<Vec<_>>();
+                quote! {
+                    (
+                        #ident::#variant { #(#fields: #a),* },
+                        #ident::#variant { #(#fields: #b),* },
+                    ) => true #(&& #a == #b)*,
+                }
+            }
+        }
+    }
+}
+
+/// Rejects fields whose variants in the error enum would have the same name,
+/// like those of `max_len` and `maxLen`.
+pub fn check_variants(fields: &[Field], attrs: &StructAttrs) -> syn::Result<()> {
+    let mut errors = Errors::default();
+    let mut seen = BTreeMap::new();
+    for f in fields {
+        let Some(variant) = field_variant(f, attrs) else {
+            continue;
+        };
+        if let Some(first) = seen.insert(variant.to_string(), &f.ident) {
+            let msg = format!(
+                "fields `{}` and `{}` would both be reported as `{}` in the builder's error",
+                first.unraw(),
+                f.ident.unraw(),
+                variant,
+            );
+            errors.push(syn::Error::new(f.ident.span(), msg));
+        }
+    }
+    errors.finish()
+}
+
+/// The variant of the error enum reporting that `field` is missing or that
+/// its sub-builder failed, if `build()` can fail because of it.
+fn field_variant(field: &Field, attrs: &StructAttrs) -> Option<Ident> {
+    match field.kind {
+        // A typestate builder cannot be built with a field missing.
+        _ if field.is_required() && !attrs.typestate => Some(missing_variant(&field.ident)),
+        Kind::Nested { .. } => Some(nested_variant(&field.ident)),
+        _ => None,
+    }
+}
+
+fn missing_variant(field: &Ident) -> Ident {
+    format_ident!("Missing{}", camel_case(field))
+}
+
+/// Prefixed like `missing_variant`, so that neither can take the name of the
+/// other or of a variant that is not about a field. The prefix also keeps the
+/// variant of tuple struct field `_0` a valid identifier.
+fn nested_variant(field: &Ident) -> Ident {
+    format_ident!("Nested{}", camel_case(field))
+}
@@ -0,0 +1,512 @@
+use crate::attr::{Errors, FieldAttrs, StructAttrs};
+use crate::error::{check_variants, BuilderError};
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::ext::IdentExt;
+use syn::{
+    parse_quote, Attribute, Fields, GenericArgument, Ident, Index, LitStr, Member, Path,
+    PathArguments, Type, Visibility,
+};
+
+pub struct Field<'a> {
+    /// Name of the field in the builder and of its setter; `_0`, `_1`, ... for
+    /// tuple struct fields unless renamed.
+    pub ident: Ident,
+    /// Name of the setter taking the whole value, or `None` with
+    /// `setter(skip)`, which leaves the field without any setter.
+    pub setter: Option<Ident>,
+    /// Visibility of the setters.
+    pub vis: Visibility,
+    /// The field in the struct being built.
+    pub member: Member,
+    pub ty: &'a Type,
+    pub kind: Kind<'a>,
+    pub default: Option<DefaultValue>,
+    pub into: bool,
+    /// `#[builder(env = "...")]`
+    pub env: Option<LitStr>,
+    /// The field's own doc comments, repeated on its setters.
+    pub docs: Vec<&'a Attribute>,
+}
+
+pub enum Kind<'a> {
+    /// Must be set before the struct can be built.
+    Required,
+    /// `Option<T>`; the setter takes the inner `T`.
+    Optional(&'a Type),
+    /// `#[builder(each = "...")]` on a collection; starts out empty.
+    Repeated { each: Ident, elem: Element },
+    /// `#[builder(sub_builder)]`; the builder holds the field type's own
+    /// builder, whose errors are reported through `error`.
+    Nested {
+        builder: Box<Type>,
+        error: Box<Path>,
+    },
+}
+
+/// What `build()` uses for a field that was never set.
+pub enum DefaultValue {
+    /// `#[builder(default)]` or `#[builder(default = "...")]`.
+    Expr(TokenStream),
+    /// `#[builder(default_with = "...")]`, called and returning early on error.
+    With(Path),
+    /// `#[builder(default_async = "...")]`, like `With` but awaited.
+    Async(Path),
+}
+
+/// What the `each` setter adds to a collection.
+pub enum Element {
+    /// A single item, as for `Vec<T>` or `HashSet<T>`.
+    Item(Box<Type>),
+    /// A single item of the type given by `each(item = "...")`, as for
+    /// `String`, which is not `IntoIterator`.
+    Explicit(Box<Type>),
+    /// A key and a value, as for `HashMap<K, V>` or `BTreeMap<K, V>`.
+    Entry(Box<Type>, Box<Type>),
+}
+
+impl<'a> Field<'a> {
+    pub fn parse_all(fields: &'a Fields, struct_attrs: &StructAttrs) -> syn::Result<Vec<Self>> {
+        let mut errors = Errors::default();
+        let fields = fields
+            .iter()
+            .enumerate()
+            .filter_map(|(index, field)| errors.ok(Field::new(index, field, struct_attrs)))
+            .collect::<Vec<_>>();
+        errors.finish()?;
+        check_variants(&fields, struct_attrs)?;
+        Ok(fields)
+    }
+
+    pub fn new(
+        index: usize,
+        field: &'a syn::Field,
+        struct_attrs: &StructAttrs,
+    ) -> syn::Result<Self> {
+        let ty = &field.ty;
+        let attrs = FieldAttrs::parse(&field.attrs)?;
+
+        let (ident, member) = match (&field.ident, attrs.name) {
+            (Some(ident), None) => (ident.clone(), Member::Named(ident.clone())),
+            (Some(_), Some(name)) => {
+                return Err(syn::Error::new_spanned(
+                    name,
+                    "`name` is only needed for fields of tuple structs",
+                ))
+            }
+            (None, name) => (
+                name.unwrap_or_else(|| format_ident!("_{}", index)),
+                Member::Unnamed(Index::from(index)),
+            ),
+        };
+
+        if let Some(env) = &attrs.env {
+            let unsupported = if struct_attrs.no_std {
+                Some("`env` reads the environment through std and is not available with `no_std`")
+            } else if struct_attrs.typestate {
+                Some("`env` is not supported by typestate builders")
+            } else if attrs.sub_builder {
+                Some("`env` cannot be combined with `sub_builder`")
+            } else if attrs.each.is_some() {
+                Some("`env` cannot be combined with `each`")
+            } else {
+                None
+            };
+            if let Some(msg) = unsupported {
+                return Err(syn::Error::new(env.span(), msg));
+            }
+        }
+
+        if struct_attrs.constant {
+            let unsupported = [
+                (attrs.each.is_some(), "each"),
+                (attrs.sub_builder, "sub_builder"),
+                (attrs.setter_into, "setter(into)"),
+                (attrs.setter_skip, "setter(skip)"),
+                (attrs.default_with.is_some(), "default_with"),
+                (attrs.default_async.is_some(), "default_async"),
+                (attrs.env.is_some(), "env"),
+            ];
+            if let Some((_, key)) = unsupported.iter().find(|(given, _)| *given) {
+                return Err(syn::Error::new_spanned(
+                    field,
+                    format!("const builders do not support `{}`", key),
+                ));
+            }
+            if attrs.default_trait {
+                return Err(syn::Error::new_spanned(
+                    field,
+                    "const builders cannot call `Default::default()`; \
+                     give the default as a const expression, as in `default = \"0\"`",
+                ));
+            }
+        }
+
+        let mut default = None;
+        let defaults = [
+            attrs.default.map(DefaultValue::Expr),
+            attrs.default_with.map(DefaultValue::With),
+            attrs.default_async.map(DefaultValue::Async),
+        ];
+        for value in defaults.into_iter().flatten() {
+            if let DefaultValue::Async(path) = &value {
+                if !struct_attrs.async_build {
+                    return Err(syn::Error::new_spanned(
+                        path,
+                        "`default_async` requires `#[builder(async_build)]` on the struct",
+                    ));
+                }
+            }
+            if default.is_some() {
+                return Err(syn::Error::new_spanned(
+                    &field.ident,
+                    "only one of `default`, `default_with` and `default_async` can be given",
+                ));
+            }
+            default = Some(value);
+        }
+        let kind = if attrs.sub_builder {
+            if let Some(each) = attrs.each {
+                return Err(syn::Error::new_spanned(
+                    each,
+                    "`each` cannot be combined with `sub_builder`",
+                ));
+            }
+            let (builder, error) = sub_builder_types(ty)?;
+            Kind::Nested {
+                builder: Box::new(builder),
+                error: Box::new(error),
+            }
+        } else if let Some(each) = attrs.each {
+            Kind::Repeated {
+                each,
+                elem: match attrs.each_item {
+                    Some(item) => Element::Explicit(Box::new(item)),
+                    None => Element::of(ty),
+                },
+            }
+        } else if let Some(inner) = generic_arg(ty, "Option") {
+            match &attrs.strip_option {
+                // Set as a whole like any other field, yet not required.
+                Some(strip) if !strip.value => {
+                    default.get_or_insert_with(|| {
+                        DefaultValue::Expr(quote!(::std::option::Option::None))
+                    });
+                    Kind::Required
+                }
+                _ => Kind::Optional(inner),
+            }
+        } else {
+            if let Some(strip) = &attrs.strip_option {
+                return Err(syn::Error::new_spanned(
+                    strip,
+                    "`strip_option` only applies to fields of type `Option<T>`",
+                ));
+            }
+            Kind::Required
+        };
+        // A field without setters can only ever get its default.
+        if attrs.setter_skip {
+            default.get_or_insert_with(|| {
+                DefaultValue::Expr(quote!(::std::default::Default::default()))
+            });
+        }
+
+        let setter = if attrs.setter_skip {
+            None
+        } else {
+            Some(attrs.setter_name.unwrap_or_else(|| ident.clone()))
+        };
+
+        Ok(Field {
+            setter,
+            vis: attrs.vis.unwrap_or_else(|| parse_quote!(pub)),
+            ident,
+            member,
+            ty,
+            kind,
+            default,
+            into: attrs.setter_into || struct_attrs.setter_into,
+            env: attrs.env,
+            docs: field
+                .attrs
+                .iter()
+                .filter(|attr| attr.path().is_ident("doc"))
+                .collect(),
+        })
+    }
+
+    pub fn is_required(&self) -> bool {
+        matches!(self.kind, Kind::Required) && self.default.is_none()
+    }
+
+    /// The type held inside the builder's `Option` for this field.
+    pub fn storage_ty(&self) -> &Type {
+        match &self.kind {
+            Kind::Optional(inner) => inner,
+            Kind::Nested { builder, .. } => builder,
+            Kind::Required | Kind::Repeated { .. } => self.ty,
+        }
+    }
+
+    /// The parameter type of a setter for a value of type `ty`, and the
+    /// expression turning its argument `arg` into that value.
+    pub fn setter_arg(&self, arg: &Ident, ty: &Type) -> (TokenStream, TokenStream) {
+        if self.into {
+            (
+                quote!(impl ::std::convert::Into<#ty>),
+                quote!(::std::convert::Into::into(#arg)),
+            )
+        } else {
+            (quote!(#ty), quote!(#arg))
+        }
+    }
+
+    /// The setter taking the whole value, unless it is skipped or would clash
+    /// with the one-element-at-a-time setter.
+    pub fn whole_setter(&self) -> Option<&Ident> {
+        let setter = self.setter.as_ref()?;
+        match &self.kind {
+            Kind::Repeated { each, .. } if each == setter => None,
+            _ => Some(setter),
+        }
+    }
+
+    /// Doc attributes for a setter of the field: the field's own docs, or
+    /// `summary` if it has none, followed by a note on whether the field is
+    /// required.
+    pub fn setter_doc(&self, summary: &str) -> TokenStream {
+        let docs = &self.docs;
+        let summary = docs.is_empty().then(|| {
+            let summary = format!(" {}", summary);
+            quote!(#[doc = #summary])
+        });
+        let note = format!(" {}", self.doc_note());
+        quote! {
+            #summary
+            #(#docs)*
+            #[doc = ""]
+            #[doc = #note]
+        }
+    }
+
+    fn doc_note(&self) -> String {
+        match (&self.kind, &self.default) {
+            (Kind::Repeated { each, .. }, _) => format!(
+                "Repeated: `{}()` adds one item at a time, and the collection is empty if \
+                 nothing is added.",
+                each,
+            ),
+            (Kind::Nested { .. }, _) => {
+                "Built through its own builder, which starts out empty.".to_owned()
+            }
+            (_, Some(_)) => "Optional: `build()` uses a default if it is not set.".to_owned(),
+            (Kind::Optional(_), None) => {
+                "Optional: the field is `None` if it is not set.".to_owned()
+            }
+            (Kind::Required, None) => "Required: it has to be set before building.".to_owned(),
+        }
+    }
+
+    /// The builder's stored `Option` for the field holding `value`, taken from
+    /// an existing struct.
+    pub fn stored_expr(&self, value: TokenStream) -> TokenStream {
+        match self.kind {
+            Kind::Optional(_) => value,
+            Kind::Nested { .. } => quote! {
+                ::std::option::Option::Some(::std::convert::From::from(#value))
+            },
+            Kind::Required | Kind::Repeated { .. } => quote!(::std::option::Option::Some(#value)),
+        }
+    }
+
+    /// Expression turning `value`, the builder's stored `Option`, into the
+    /// value of the field, returning early with a `BuilderError` if that is
+    /// not possible.
+    pub fn build_expr(&self, value: TokenStream, error: &BuilderError) -> TokenStream {
+        if let Kind::Nested { .. } = self.kind {
+            let nested = error.nested(&self.ident);
+            let failed = quote!(return ::std::result::Result::Err(#nested(err)));
+            return self.build_nested_expr(value, error, failed);
+        }
+        self.unwrap_expr(value, error, error.missing(&self.ident))
+    }
+
+    /// Like `build_expr`, for a field that is known to be set if it is
+    /// required.
+    pub fn build_set_expr(&self, value: TokenStream, error: &BuilderError) -> TokenStream {
+        self.unwrap_expr(value, error, quote!(::std::unreachable!()))
+    }
+
+    /// Expression taking the value out of the stored `Option` in `value`, or
+    /// falling back to the default, an empty value or `missing`.
+    fn unwrap_expr(
+        &self,
+        value: TokenStream,
+        error: &BuilderError,
+        missing: TokenStream,
+    ) -> TokenStream {
+        let present = match self.kind {
+            Kind::Optional(_) => quote!(::std::option::Option::Some(value)),
+            Kind::Required | Kind::Repeated { .. } | Kind::Nested { .. } => quote!(value),
+        };
+        let fallback = match (&self.default, &self.kind) {
+            (Some(default), _) => self.default_expr(default, error),
+            (None, Kind::Optional(_)) => quote!(::std::option::Option::None),
+            (None, Kind::Repeated { .. }) => quote!(::std::default::Default::default()),
+            (None, Kind::Required | Kind::Nested { .. }) => missing,
+        };
+        quote! {
+            match #value {
+                ::std::option::Option::Some(value) => #present,
+                ::std::option::Option::None => #fallback,
+            }
+        }
+    }
+
+    /// Expression evaluating to the default, returning early with a
+    /// `BuilderError` if a `default_with` or `default_async` function fails.
+    pub fn default_expr(&self, default: &DefaultValue, error: &BuilderError) -> TokenStream {
+        let call = match default {
+            DefaultValue::Expr(expr) => return expr.clone(),
+            DefaultValue::With(path) => quote!(#path()),
+            DefaultValue::Async(path) => quote!(#path().await),
+        };
+        let failed = error.default_failed(&self.ident, quote!(err));
+        quote! {
+            match #call {
+                ::std::result::Result::Ok(value) => value,
+                ::std::result::Result::Err(err) => #failed,
+            }
+        }
+    }
+
+    /// Expression building the sub-builder in the stored `Option` in `value`,
+    /// or an empty one if it was never touched. Its error `err` is handled by
+    /// `failed`, which has to diverge.
+    pub fn build_nested_expr(
+        &self,
+        value: TokenStream,
+        error: &BuilderError,
+        failed: TokenStream,
+    ) -> TokenStream {
+        let ty = self.ty;
+        let fallback = match &self.default {
+            Some(default) => {
+                let default = self.default_expr(default, error);
+                quote!(::std::result::Result::Ok(#default))
+            }
+            None => quote!(<#ty>::builder().build()),
+        };
+        quote! {
+            {
+                let built = match #value {
+                    ::std::option::Option::Some(builder) => builder.build(),
+                    ::std::option::Option::None => #fallback,
+                };
+                match built {
+                    ::std::result::Result::Ok(value) => value,
+                    ::std::result::Result::Err(err) => #failed,
+                }
+            }
+        }
+    }
+}
+
+impl Element {
+    /// Works out the element type of the collection `ty` from how it is
+    /// written. Types whose last path segment ends in `Map` and that have two
+    /// or more type arguments are taken to be maps from the first to the
+    /// second; other types with type arguments hold values of the first one.
+    /// Without type arguments, like `String`, the collection has to be
+    /// `IntoIterator` unless `each(item = "...")` gives the type instead.
+    fn of(ty: &Type) -> Self {
+        match type_args(ty) {
+            Some((ident, args)) if args.len() >= 2 && ident.to_string().ends_with("Map") => {
+                Element::Entry(Box::new(args[0].clone()), Box::new(args[1].clone()))
+            }
+            Some((_, args)) if !args.is_empty() => Element::Item(Box::new(args[0].clone())),
+            _ => Element::Item(parse_quote!(<#ty as ::std::iter::IntoIterator>::Item)),
+        }
+    }
+
+    /// The item type of an iterator extending the collection.
+    pub fn item_ty(&self) -> Type {
+        match self {
+            Element::Item(ty) | Element::Explicit(ty) => (**ty).clone(),
+            Element::Entry(key, value) => parse_quote!((#key, #value)),
+        }
+    }
+}
+
+/// Returns `T` if `ty` is written as `Wrapper<T>`.
+pub fn generic_arg<'a>(ty: &'a Type, wrapper: &str) -> Option<&'a Type> {
+    match type_args(ty)? {
+        (ident, args) if ident == wrapper && args.len() == 1 => Some(args[0]),
+        _ => None,
+    }
+}
+
+/// The last segment of the path `ty` and the types among its generic
+/// arguments.
+fn type_args(ty: &Type) -> Option<(&Ident, Vec<&Type>)> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    if path.qself.is_some() {
+        return None;
+    }
+    let segment = path.path.segments.last()?;
+    let args = match &segment.arguments {
+        PathArguments::AngleBracketed(args) => args
+            .args
+            .iter()
+            .filter_map(|arg| match arg {
+                GenericArgument::Type(ty) => Some(ty),
+                _ => None,
+            })
+            .collect(),
+        PathArguments::None => Vec::new(),
+        PathArguments::Parenthesized(_) => return None,
+    };
+    Some((&segment.ident, args))
+}
+
+/// The builder and error types generated for `ty` by `derive(Builder)`:
+/// `path::Inner<T>` -> (`path::InnerBuilder<T>`, `path::InnerBuilderError`).
+fn sub_builder_types(ty: &Type) -> syn::Result<(Type, Path)> {
+    let path =
+        match ty {
+            Type::Path(path) if path.qself.is_none() => &path.path,
+            _ => return Err(syn::Error::new_spanned(
+                ty,
+                "`sub_builder` requires the field's type to be a path to a type deriving `Builder`",
+            )),
+        };
+
+    let mut builder = path.clone();
+    let last = builder.segments.last_mut().unwrap();
+    last.ident = format_ident!("{}Builder", last.ident);
+
+    let mut error = builder.clone();
+    let last = error.segments.last_mut().unwrap();
+    last.ident = format_ident!("{}Error", last.ident);
+    last.arguments = PathArguments::None;
+
+    Ok((parse_quote!(#builder), error))
+}
+
+/// `current_dir` -> `CurrentDir`, and `r#type` -> `Type`.
+pub fn camel_case(ident: &Ident) -> String {
+    ident
+        .unraw()
+        .to_string()
+        .split('_')
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            let first = chars.next().unwrap().to_ascii_uppercase();
+            std::iter::once(first).chain(chars).collect::<String>()
+        })
+        .collect()
+}
@@ -0,0 +1,290 @@
+mod attr;
+mod constant;
+mod env;
+mod error;
+mod field;
+mod merge;
+mod no_std;
+mod partial;
+mod setter;
+mod target;
+mod typestate;
+
+use crate::attr::{Errors, FieldAttrs, Pattern, StructAttrs};
+use crate::error::BuilderError;
+use crate::field::{Field, Kind};
+use crate::target::Target;
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, parse_quote, Data, DataStruct, DeriveInput, Fields};
+
+#[proc_macro_derive(Builder, attributes(builder))]
+pub fn derive(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    expand(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    // Keep going after a malformed attribute to report all of them at once.
+    let mut errors = Errors::default();
+    let attrs = errors
+        .ok(StructAttrs::parse(&input.attrs))
+        .unwrap_or_default();
+
+    let targets = match &input.data {
+        Data::Struct(DataStruct { fields, .. }) => errors
+            .ok(Target::from_struct(input, &attrs, fields))
+            .into_iter()
+            .collect::<Vec<_>>(),
+        Data::Enum(data) => data
+            .variants
+            .iter()
+            .filter_map(|variant| {
+                for attr in variant
+                    .attrs
+                    .iter()
+                    .filter(|a| a.path().is_ident("builder"))
+                {
+                    errors.push(syn::Error::new_spanned(
+                        attr,
+                        "`#[builder]` is not supported on enum variants; \
+                         put options on the enum or on the fields",
+                    ));
+                }
+                match variant.fields {
+                    Fields::Named(_) => errors.ok(Target::from_variant(input, &attrs, variant)),
+                    // Unit and tuple variants are constructed directly.
+                    _ => {
+                        for field in &variant.fields {
+                            check_unused_attrs(field, &mut errors);
+                        }
+                        None
+                    }
+                }
+            })
+            .collect(),
+        _ => {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "Builder can only be derived for structs and enums",
+            ))
+        }
+    };
+    errors.finish()?;
+
+    let expanded = targets.iter().map(expand_target).collect();
+    Ok(if attrs.no_std {
+        no_std::expand(expanded)
+    } else {
+        expanded
+    })
+}
+
+/// Reports the `#[builder]` attributes of a field that gets no builder, which
+/// are malformed or have no effect.
+fn check_unused_attrs(field: &syn::Field, errors: &mut Errors) {
+    let Some(attr) = field.attrs.iter().find(|a| a.path().is_ident("builder")) else {
+        return;
+    };
+    if errors.ok(FieldAttrs::parse(&field.attrs)).is_some() {
+        errors.push(syn::Error::new_spanned(
+            attr,
+            "tuple variants have no builder, so `#[builder]` on their fields has no effect",
+        ));
+    }
+}
+
+fn expand_target(target: &Target) -> TokenStream2 {
+    let builder = if target.attrs.typestate {
+        typestate::expand(target)
+    } else if target.attrs.constant {
+        constant::expand(target)
+    } else {
+        expand_builder(target)
+    };
+    let buildable = target.buildable();
+    quote! {
+        #builder
+        #buildable
+    }
+}
+
+fn expand_builder(target: &Target) -> TokenStream2 {
+    let vis = target.vis;
+    let ty = target.ty;
+    let ctor = &target.ctor;
+    let builder_fn = &target.builder_fn;
+    let builder = &target.builder;
+    let fields = &target.fields;
+    let attrs = target.attrs;
+    let error = BuilderError::new(target);
+    let error_ident = &error.ident;
+    let error_enum = error.expand();
+    let missing_fields_from = partial::enabled(target).then(|| error.impl_missing_fields());
+    let validate = error.validate(&format_ident!("value"));
+    let phantom_field = target.phantom_field();
+    let phantom_init = target.phantom_init();
+    let builder_doc = target.builder_doc();
+    let builder_fn_doc = target.builder_fn_doc();
+    let build_doc = format!(
+        " Builds [`{}`], or fails if a required field is not set{}.",
+        target.doc_name(),
+        if attrs.validate.is_some() {
+            " or the value does not pass validation"
+        } else {
+            ""
+        },
+    );
+
+    let generics = target.generics;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let idents = fields.iter().map(|f| &f.ident).collect::<Vec<_>>();
+    let members = fields.iter().map(|f| &f.member);
+    let storage = fields.iter().map(Field::storage_ty).collect::<Vec<_>>();
+    let setters = fields.iter().map(|f| setter::setters(f, attrs.pattern));
+    let state_methods = fields
+        .iter()
+        .map(|f| setter::state_methods(f, attrs.pattern));
+    let impl_from = target.impl_from(quote!(#builder #ty_generics), phantom_init.clone());
+    let merge = merge::expand(fields, attrs.pattern);
+    let from_env = env::expand(target, &error);
+    let partial = partial::expand(target, &error);
+    let build_partial_impl = partial::impl_build_partial(target, &error);
+    let inits = fields.iter().map(|f| {
+        let ident = &f.ident;
+        // Sub-builders are built in place rather than cloned.
+        let value = match (attrs.pattern, &f.kind) {
+            (Pattern::Owned, _) => quote!(self.#ident),
+            (Pattern::Mutable, Kind::Nested { .. }) => quote!(&mut self.#ident),
+            (Pattern::Immutable, Kind::Nested { .. }) => quote!(&self.#ident),
+            (Pattern::Mutable | Pattern::Immutable, _) => {
+                quote!(::std::clone::Clone::clone(&self.#ident))
+            }
+        };
+        f.build_expr(value, &error)
+    });
+    let cloned = fields
+        .iter()
+        .filter(|f| !matches!(f.kind, Kind::Nested { .. }))
+        .map(Field::storage_ty)
+        .collect::<Vec<_>>();
+
+    // Immutable setters clone the builder. Deriving Clone for the others would
+    // fail for field types that are not Clone themselves.
+    let derive_clone =
+        (attrs.pattern == Pattern::Immutable).then(|| quote!(#[derive(::std::clone::Clone)]));
+    let (receiver, clone_bounds) = match attrs.pattern {
+        Pattern::Mutable => (quote!(&mut self), Some(&cloned)),
+        Pattern::Owned => (quote!(self), None),
+        Pattern::Immutable => (quote!(&self), Some(&cloned)),
+    };
+    let mut ops_generics = generics.clone();
+    if let Some(storage) = clone_bounds {
+        let where_clause = ops_generics.make_where_clause();
+        for storage in storage {
+            where_clause
+                .predicates
+                .push(parse_quote!(#storage: ::std::clone::Clone));
+        }
+    }
+    let (_, _, ops_where_clause) = ops_generics.split_for_impl();
+    // `BuilderOps::build` takes the builder by value whatever the pattern.
+    let (ops_receiver, ops_arg) = match attrs.pattern {
+        Pattern::Mutable => (quote!(mut self), quote!(&mut self)),
+        Pattern::Owned => (quote!(self), quote!(self)),
+        Pattern::Immutable => (quote!(self), quote!(&self)),
+    };
+    // `BuilderOps::build` is not async, so an async `build()` cannot back it.
+    let asyncness = attrs.async_build.then(|| quote!(async));
+    let builder_ops = (!attrs.async_build).then(|| {
+        quote! {
+            impl #impl_generics ::derive_builder::BuilderOps for #builder #ty_generics #ops_where_clause {
+                type Target = #ty #ty_generics;
+                type Error = #error_ident;
+
+                fn build(#ops_receiver) -> ::std::result::Result<#ty #ty_generics, #error_ident> {
+                    Self::build(#ops_arg)
+                }
+            }
+        }
+    });
+    let clone_bounds = clone_bounds.map(|storage| {
+        quote! {
+            where
+                #(#storage: ::std::clone::Clone,)*
+        }
+    });
+
+    // Every field of the builder is optional, so a partial configuration
+    // deserializes into a builder whose remaining fields are not set yet.
+    let derive_deserialize = attrs.deserialize.then(|| {
+        quote! {
+            #[derive(::serde::Deserialize)]
+            #[serde(default)]
+        }
+    });
+
+    quote! {
+        impl #impl_generics #ty #ty_generics #where_clause {
+            #builder_fn_doc
+            pub fn #builder_fn() -> #builder #ty_generics {
+                #builder {
+                    #(#idents: ::std::option::Option::None,)*
+                    #phantom_init
+                }
+            }
+        }
+
+        #builder_doc
+        #derive_clone
+        #derive_deserialize
+        #vis struct #builder #generics #where_clause {
+            #(#idents: ::std::option::Option<#storage>,)*
+            #phantom_field
+        }
+
+        impl #impl_generics ::std::default::Default for #builder #ty_generics #where_clause {
+            fn default() -> Self {
+                #ty::#builder_fn()
+            }
+        }
+
+        #impl_from
+
+        #error_enum
+
+        #missing_fields_from
+
+        impl #impl_generics #builder #ty_generics #where_clause {
+            #from_env
+
+            #(#setters)*
+
+            #(#state_methods)*
+
+            #merge
+
+            #partial
+
+            #[doc = #build_doc]
+            pub #asyncness fn build(#receiver) -> ::std::result::Result<#ty #ty_generics, #error_ident>
+            #clone_bounds
+            {
+                let value = #ctor {
+                    #(#members: #inits,)*
+                };
+                #validate
+                ::std::result::Result::Ok(value)
+            }
+        }
+
+        #builder_ops
+
+        #build_partial_impl
+    }
+}
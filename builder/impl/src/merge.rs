@@ -0,0 +1,91 @@
+//! `merge()`, which overlays the fields set in another builder of the same
+//! type.
+
+use crate::attr::Pattern;
+use crate::field::{Element, Field, Kind};
+use crate::setter;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::parse_quote;
+
+pub fn expand(fields: &[Field], pattern: Pattern) -> TokenStream {
+    // A collection whose element type was given with `each(item = "...")`,
+    // like `String`, need not iterate over its elements, so the other one is
+    // appended whole, as `String: Extend<String>` allows. For a collection
+    // that cannot do that `merge()` is left uncallable rather than failing
+    // the derive, as `for<'__builder>` defers the bound.
+    let predicates = fields
+        .iter()
+        .filter(|f| explicit_item(f))
+        .map(|f| {
+            let ty = f.ty;
+            quote!(for<'__builder> #ty: ::std::iter::Extend<#ty>)
+        })
+        .collect::<Vec<_>>();
+    let merge = setter::bounded_method(
+        pattern,
+        &parse_quote!(pub),
+        &format_ident!("merge"),
+        quote!(other: Self),
+        &predicates,
+        |this| {
+            let fields = fields.iter().map(|f| merge_field(f, this, pattern));
+            quote!(#(#fields)*)
+        },
+    );
+    quote! {
+        /// Overlays the fields set in `other` onto this builder. Collections
+        /// are appended to and sub-builders are merged in turn.
+        #merge
+    }
+}
+
+/// Collections are appended to and sub-builders are merged recursively. Any
+/// other field set in `other` replaces the value in `this`.
+fn merge_field(field: &Field, this: &TokenStream, pattern: Pattern) -> TokenStream {
+    let ident = &field.ident;
+    let merged = match (&field.kind, pattern) {
+        (Kind::Repeated { .. }, _) if explicit_item(field) => quote! {
+            ::std::iter::Extend::extend(mine, ::std::iter::once(theirs));
+        },
+        (Kind::Repeated { .. }, _) => quote! {
+            ::std::iter::Extend::extend(mine, theirs);
+        },
+        (Kind::Nested { .. }, Pattern::Mutable) => quote! {
+            mine.merge(theirs);
+        },
+        (Kind::Nested { .. }, Pattern::Owned) => quote! {
+            let taken = ::std::mem::take(mine);
+            *mine = taken.merge(theirs);
+        },
+        (Kind::Nested { .. }, Pattern::Immutable) => quote! {
+            *mine = mine.merge(theirs);
+        },
+        (Kind::Required | Kind::Optional(_), _) => quote! {
+            *mine = theirs;
+        },
+    };
+    quote! {
+        if let ::std::option::Option::Some(theirs) = other.#ident {
+            match #this.#ident {
+                ::std::option::Option::Some(ref mut mine) => {
+                    #merged
+                }
+                ::std::option::Option::None => {
+                    #this.#ident = ::std::option::Option::Some(theirs);
+                }
+            }
+        }
+    }
+}
+
+/// Whether `field` is a collection with `each(item = "...")`.
+fn explicit_item(field: &Field) -> bool {
+    matches!(
+        field.kind,
+        Kind::Repeated {
+            elem: Element::Explicit(_),
+            ..
+        }
+    )
+}
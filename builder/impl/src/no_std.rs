@@ -0,0 +1,69 @@
+//! Expansion for `#[builder(no_std)]`.
+//!
+//! Everything the expansion refers to is written as a `::std::...` path so
+//! that it cannot be shadowed (09-redefined-prelude-types). For a `#![no_std]`
+//! crate those paths are redirected to `::alloc` for the items that need an
+//! allocator and to `::core` for all others, including `core::error::Error`.
+
+use proc_macro2::{Group, Ident, Spacing, TokenStream, TokenTree};
+
+/// Modules of std whose items live in `alloc` rather than `core`.
+const ALLOC_MODULES: &[&str] = &["boxed", "string", "vec"];
+
+pub fn expand(tokens: TokenStream) -> TokenStream {
+    let tokens = tokens.into_iter().collect::<Vec<_>>();
+    tokens
+        .iter()
+        .enumerate()
+        .map(|(i, token)| match token {
+            TokenTree::Group(group) => {
+                let mut expanded = Group::new(group.delimiter(), expand(group.stream()));
+                expanded.set_span(group.span());
+                TokenTree::Group(expanded)
+            }
+            TokenTree::Ident(ident) if ident == "std" && is_root(&tokens[..i]) => {
+                let krate = match tokens.get(i + 3) {
+                    Some(TokenTree::Ident(module))
+                        if ALLOC_MODULES.iter().any(|alloc| module == alloc) =>
+                    {
+                        "alloc"
+                    }
+                    _ => "core",
+                };
+                TokenTree::Ident(Ident::new(krate, ident.span()))
+            }
+            _ => token.clone(),
+        })
+        .collect()
+}
+
+/// Whether `std` after the tokens `before` is the root of a path, as in
+/// `::std::vec::Vec`. A `std` module further down a path the user wrote, like
+/// `shim::std::zero()` in a default, is left alone.
+fn is_root(before: &[TokenTree]) -> bool {
+    let [rest @ .., TokenTree::Punct(first), TokenTree::Punct(second)] = before else {
+        return false;
+    };
+    if first.as_char() != ':' || second.as_char() != ':' {
+        return false;
+    }
+    // Whatever comes before the `::` would otherwise be a path segment.
+    match rest {
+        [.., TokenTree::Punct(quote), TokenTree::Ident(_)] if quote.as_char() == '\'' => true,
+        [.., TokenTree::Ident(ident)] => KEYWORDS.iter().any(|keyword| ident == keyword),
+        // `-> ::std::...` and `=> ::std::...`, but not `<T as Trait>::std`.
+        [.., TokenTree::Punct(arrow), TokenTree::Punct(close)] if close.as_char() == '>' => {
+            arrow.spacing() == Spacing::Joint && matches!(arrow.as_char(), '-' | '=')
+        }
+        [.., TokenTree::Punct(close)] => close.as_char() != '>',
+        _ => true,
+    }
+}
+
+/// Keywords a path can follow. `crate`, `self`, `super` and `Self` are left
+/// out, as they start a path themselves.
+const KEYWORDS: &[&str] = &[
+    "as", "async", "box", "break", "const", "dyn", "else", "enum", "extern", "fn", "for", "if",
+    "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref", "return", "static",
+    "struct", "trait", "type", "unsafe", "use", "where", "while", "yield",
+];
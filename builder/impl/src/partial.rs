@@ -0,0 +1,211 @@
+//! `missing_fields()`, listing every required field that is not set yet, and
+//! `build_partial()`, which reports all of them at once rather than the first
+//! one as `build()` does.
+
+use crate::error::BuilderError;
+use crate::field::Kind;
+use crate::target::Target;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::ext::IdentExt;
+
+/// Whether the builder for `target` gets the methods. The list of names needs
+/// an allocator, which a `no_std` crate may not have, and `MissingFields` only
+/// exists with the `alloc` feature of derive_builder.
+pub fn enabled(target: &Target) -> bool {
+    cfg!(feature = "alloc") && !target.attrs.no_std
+}
+
+/// Whether the builder gets `build_partial()` too. With other ways for
+/// `build()` to fail, not every error could be reported as `MissingFields`.
+fn has_build_partial(target: &Target, error: &BuilderError) -> bool {
+    enabled(target) && error.only_missing()
+}
+
+pub fn expand(target: &Target, error: &BuilderError) -> TokenStream {
+    if !enabled(target) {
+        return TokenStream::new();
+    }
+
+    let ty = target.ty;
+    let ctor = &target.ctor;
+    let fields = &target.fields;
+    let (_, ty_generics, _) = target.generics.split_for_impl();
+
+    // A sub-builder is listed if it has missing fields, one that was never
+    // touched if an empty one would have them.
+    let checks = fields.iter().filter_map(|f| {
+        let ident = &f.ident;
+        let name = ident.unraw().to_string();
+        match &f.kind {
+            Kind::Required if f.is_required() => Some(quote! {
+                if ::std::option::Option::is_none(&self.#ident) {
+                    missing.push(#name);
+                }
+            }),
+            Kind::Nested { .. } => {
+                let field_ty = f.ty;
+                let untouched = if f.default.is_some() {
+                    quote!(false)
+                } else {
+                    quote!(!<#field_ty>::builder().missing_fields().is_empty())
+                };
+                Some(quote! {
+                    let incomplete = match self.#ident {
+                        ::std::option::Option::Some(ref builder) => {
+                            !builder.missing_fields().is_empty()
+                        }
+                        ::std::option::Option::None => #untouched,
+                    };
+                    if incomplete {
+                        missing.push(#name);
+                    }
+                })
+            }
+            _ => None,
+        }
+    });
+    let missing_fields = quote! {
+        /// The names of the required fields that are not set yet, all of
+        /// which `build()` needs. A sub-builder is listed if one of its own
+        /// required fields is not set.
+        pub fn missing_fields(&self) -> ::std::vec::Vec<&'static str> {
+            let mut missing = ::std::vec::Vec::new();
+            #(#checks)*
+            missing
+        }
+    };
+
+    if !has_build_partial(target, error) {
+        return missing_fields;
+    }
+
+    // Sub-builders are built first, collecting what they are missing for the
+    // error.
+    let nested = fields.iter().enumerate().filter_map(|(i, f)| {
+        let Kind::Nested { .. } = f.kind else {
+            return None;
+        };
+        let ident = &f.ident;
+        let name = ident.unraw().to_string();
+        let field_ty = f.ty;
+        let built = format_ident!("built{}", i);
+        let fallback = match &f.default {
+            Some(default) => {
+                let default = f.default_expr(default, error);
+                quote!(::std::result::Result::Ok(#default))
+            }
+            None => quote! {
+                ::derive_builder::BuildPartial::build_partial(&<#field_ty>::builder())
+            },
+        };
+        Some(quote! {
+            let #built = match self.#ident {
+                ::std::option::Option::Some(ref builder) => {
+                    ::derive_builder::BuildPartial::build_partial(builder)
+                }
+                ::std::option::Option::None => #fallback,
+            };
+            let #built = match #built {
+                ::std::result::Result::Ok(value) => ::std::option::Option::Some(value),
+                ::std::result::Result::Err(err) => {
+                    nested.push((#name, err));
+                    ::std::option::Option::None
+                }
+            };
+        })
+    });
+    let members = fields.iter().map(|f| &f.member);
+    let inits = fields.iter().enumerate().map(|(i, f)| {
+        if let Kind::Nested { .. } = f.kind {
+            let built = format_ident!("built{}", i);
+            return quote!(::std::option::Option::unwrap(#built));
+        }
+        let ident = &f.ident;
+        f.build_set_expr(quote!(::std::clone::Clone::clone(&self.#ident)), error)
+    });
+    let bounds = bounds(target);
+    let doc = format!(
+        " Builds [`{}`] like `build()`, except that it fails with every \
+         required field that is not set rather than just the first one.",
+        target.doc_name(),
+    );
+    quote! {
+        #missing_fields
+
+        #[doc = #doc]
+        pub fn build_partial(
+            &self,
+        ) -> ::std::result::Result<#ty #ty_generics, ::derive_builder::MissingFields>
+        where
+            #(#bounds)*
+        {
+            #[allow(unused_mut)]
+            let mut nested = ::std::vec::Vec::new();
+            #(#nested)*
+            let missing = self.missing_fields();
+            if !missing.is_empty() {
+                return ::std::result::Result::Err(::derive_builder::MissingFields::new(
+                    missing, nested,
+                ));
+            }
+            ::std::result::Result::Ok(#ctor {
+                #(#members: #inits,)*
+            })
+        }
+    }
+}
+
+/// `impl derive_builder::BuildPartial`, through which an outer builder builds
+/// this one as its sub-builder.
+pub fn impl_build_partial(target: &Target, error: &BuilderError) -> TokenStream {
+    if !has_build_partial(target, error) {
+        return TokenStream::new();
+    }
+    let ty = target.ty;
+    let builder = &target.builder;
+    let (impl_generics, ty_generics, where_clause) = target.generics.split_for_impl();
+    let predicates = where_clause.into_iter().flat_map(|w| &w.predicates);
+    let bounds = bounds(target);
+    quote! {
+        impl #impl_generics ::derive_builder::BuildPartial for #builder #ty_generics
+        where
+            #(#predicates,)*
+            #(#bounds)*
+        {
+            type Target = #ty #ty_generics;
+
+            fn build_partial(
+                &self,
+            ) -> ::std::result::Result<#ty #ty_generics, ::derive_builder::MissingFields> {
+                Self::build_partial(self)
+            }
+        }
+    }
+}
+
+/// Where-clause predicates of `build_partial()`. The builder is only borrowed,
+/// so the other fields are cloned out of it. Once no field is missing, a
+/// sub-builder can only fail if its own `build()` can fail otherwise, in which
+/// case it has no `build_partial()`.
+///
+/// `for<'__builder>` defers the bounds to where `build_partial()` is called,
+/// as they would be errors right away otherwise.
+fn bounds(target: &Target) -> Vec<TokenStream> {
+    target
+        .fields
+        .iter()
+        .map(|f| match &f.kind {
+            Kind::Nested { builder, .. } => {
+                let field_ty = f.ty;
+                quote! {
+                    for<'__builder> #builder: ::derive_builder::BuildPartial<Target = #field_ty>,
+                }
+            }
+            _ => {
+                let storage = f.storage_ty();
+                quote!(for<'__builder> #storage: ::std::clone::Clone,)
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,306 @@
+use crate::attr::Pattern;
+use crate::field::{generic_arg, Element, Field, Kind};
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::ext::IdentExt;
+use syn::{Ident, Type, Visibility};
+
+/// The setters of `field` for a builder following `pattern`.
+pub fn setters(field: &Field, pattern: Pattern) -> TokenStream {
+    let ident = &field.ident;
+    let vis = &field.vis;
+    let Some(setter) = &field.setter else {
+        return TokenStream::new();
+    };
+
+    if let Kind::Nested { builder, .. } = &field.kind {
+        return nested_setters(field, setter, builder, pattern);
+    }
+
+    let whole = field.whole_setter().map(|setter| {
+        let doc = field.setter_doc(&format!("Sets `{}`.", ident.unraw()));
+        let (param, value) = field.setter_arg(ident, field.storage_ty());
+        let setter = method(pattern, vis, setter, quote!(#ident: #param), |this| {
+            quote! {
+                #this.#ident = ::std::option::Option::Some(#value);
+            }
+        });
+        quote! {
+            #doc
+            #setter
+        }
+    });
+    let each = match &field.kind {
+        Kind::Repeated { each, elem } => {
+            let (params, item) = match elem {
+                Element::Item(ty) | Element::Explicit(ty) => {
+                    let (param, value) = field.setter_arg(each, ty);
+                    (quote!(#each: #param), value)
+                }
+                Element::Entry(key_ty, value_ty) => {
+                    let key = format_ident!("key");
+                    let value = format_ident!("value");
+                    let (key_param, key) = field.setter_arg(&key, key_ty);
+                    let (value_param, value) = field.setter_arg(&value, value_ty);
+                    (
+                        quote!(key: #key_param, value: #value_param),
+                        quote!((#key, #value)),
+                    )
+                }
+            };
+            let one_doc = field.setter_doc(&format!("Adds one item to `{}`.", ident.unraw()));
+            let one = method(pattern, vis, each, params, |this| {
+                quote! {
+                    ::std::iter::Extend::extend(
+                        #this.#ident.get_or_insert_with(::std::default::Default::default),
+                        ::std::iter::once(#item),
+                    );
+                }
+            });
+
+            let all_doc = field.setter_doc(&format!(
+                "Adds every item of `iter` to `{}`.",
+                ident.unraw()
+            ));
+            let extend = format_ident!("extend_{}", setter);
+            let item_ty = elem.item_ty();
+            let all = method(
+                pattern,
+                vis,
+                &extend,
+                quote!(iter: impl ::std::iter::IntoIterator<Item = #item_ty>),
+                |this| {
+                    quote! {
+                        ::std::iter::Extend::extend(
+                            #this.#ident.get_or_insert_with(::std::default::Default::default),
+                            iter,
+                        );
+                    }
+                },
+            );
+
+            Some(quote! {
+                #one_doc
+                #one
+                #all_doc
+                #all
+            })
+        }
+        Kind::Required | Kind::Optional(_) | Kind::Nested { .. } => None,
+    };
+
+    let in_place = in_place_setter(field, setter, pattern);
+
+    quote! {
+        #whole
+        #each
+        #in_place
+    }
+}
+
+/// `<field>_with(f)` handing a collection to `f` to modify in place, starting
+/// from an empty one, or `<field>_or_insert_with(f)` setting an `Option`
+/// field to what `f` returns unless it is set already.
+fn in_place_setter(field: &Field, setter: &Ident, pattern: Pattern) -> Option<TokenStream> {
+    let ident = &field.ident;
+    let vis = &field.vis;
+    let storage = field.storage_ty();
+    let collection = match field.kind {
+        Kind::Repeated { .. } => true,
+        Kind::Required => generic_arg(field.ty, "Vec").is_some(),
+        Kind::Optional(_) | Kind::Nested { .. } => false,
+    };
+
+    let (doc, method) = if collection {
+        let name = format_ident!("{}_with", setter);
+        let param = quote!(f: impl ::std::ops::FnOnce(&mut #storage));
+        let method = method(pattern, vis, &name, param, |this| {
+            quote! {
+                f(#this.#ident.get_or_insert_with(::std::default::Default::default));
+            }
+        });
+        let summary = format!(
+            "Modifies `{}` in place, starting from an empty one.",
+            ident.unraw()
+        );
+        (field.setter_doc(&summary), method)
+    } else if let Kind::Optional(_) = field.kind {
+        let name = format_ident!("{}_or_insert_with", setter);
+        let param = quote!(f: impl ::std::ops::FnOnce() -> #storage);
+        let method = method(pattern, vis, &name, param, |this| {
+            quote! {
+                #this.#ident.get_or_insert_with(f);
+            }
+        });
+        let summary = format!(
+            "Sets `{}` to the value returned by `f` unless it is set.",
+            ident.unraw()
+        );
+        (field.setter_doc(&summary), method)
+    } else {
+        return None;
+    };
+
+    Some(quote! {
+        #doc
+        #method
+    })
+}
+
+/// `is_<field>_set()` and `clear_<field>()`, unless the field's setters are
+/// skipped.
+pub fn state_methods(field: &Field, pattern: Pattern) -> TokenStream {
+    if field.setter.is_none() {
+        return TokenStream::new();
+    }
+    let ident = &field.ident;
+    let vis = &field.vis;
+    let is_set = format_ident!("is_{}_set", ident);
+    let is_set_doc = format!(" Whether `{}` has been set.", ident.unraw());
+    let clear = format_ident!("clear_{}", ident);
+    let clear_doc = format!(" Unsets `{}`, as if it had never been set.", ident.unraw());
+    let clear = method(pattern, vis, &clear, TokenStream::new(), |this| {
+        quote! {
+            #this.#ident = ::std::option::Option::None;
+        }
+    });
+    quote! {
+        #[doc = #is_set_doc]
+        #vis fn #is_set(&self) -> bool {
+            ::std::option::Option::is_some(&self.#ident)
+        }
+
+        #[doc = #clear_doc]
+        #clear
+    }
+}
+
+/// A sub-builder field gets an accessor to its builder, and a setter that
+/// hands the builder to a closure. The closure receives the sub-builder the
+/// same way the outer builder's setters receive theirs, so both builders need
+/// to follow the same pattern.
+fn nested_setters(field: &Field, setter: &Ident, builder: &Type, pattern: Pattern) -> TokenStream {
+    let ident = &field.ident;
+    let vis = &field.vis;
+    let ty = field.ty;
+    let setter_mut = format_ident!("{}_mut", setter);
+    let with_doc = field.setter_doc(&format!(
+        "Fills in `{}` through its builder.",
+        ident.unraw()
+    ));
+    let mut_doc = field.setter_doc(&format!("The builder of `{}`.", ident.unraw()));
+
+    let with = match pattern {
+        Pattern::Mutable => method(
+            pattern,
+            vis,
+            setter,
+            quote!(f: impl ::std::ops::FnOnce(&mut #builder) -> &mut #builder),
+            |this| {
+                quote! {
+                    f(#this.#ident.get_or_insert_with(<#ty>::builder));
+                }
+            },
+        ),
+        Pattern::Owned => method(
+            pattern,
+            vis,
+            setter,
+            quote!(f: impl ::std::ops::FnOnce(#builder) -> #builder),
+            |this| {
+                quote! {
+                    let builder = #this.#ident.take().unwrap_or_else(<#ty>::builder);
+                    #this.#ident = ::std::option::Option::Some(f(builder));
+                }
+            },
+        ),
+        Pattern::Immutable => method(
+            pattern,
+            vis,
+            setter,
+            quote!(f: impl ::std::ops::FnOnce(&#builder) -> #builder),
+            |this| {
+                // `#this` is itself called `builder`.
+                quote! {
+                    let nested = match #this.#ident {
+                        ::std::option::Option::Some(ref nested) => f(nested),
+                        ::std::option::Option::None => f(&<#ty>::builder()),
+                    };
+                    #this.#ident = ::std::option::Option::Some(nested);
+                }
+            },
+        ),
+    };
+
+    quote! {
+        #mut_doc
+        #vis fn #setter_mut(&mut self) -> &mut #builder {
+            self.#ident.get_or_insert_with(<#ty>::builder)
+        }
+
+        #with_doc
+        #with
+    }
+}
+
+/// A builder method called `name` taking `params`. The statements from `body`
+/// modify the builder through the expression they are given.
+pub fn method(
+    pattern: Pattern,
+    vis: &Visibility,
+    name: &Ident,
+    params: TokenStream,
+    body: impl FnOnce(&TokenStream) -> TokenStream,
+) -> TokenStream {
+    bounded_method(pattern, vis, name, params, &[], body)
+}
+
+/// Like `method`, with the where-clause `predicates` on the method.
+pub fn bounded_method(
+    pattern: Pattern,
+    vis: &Visibility,
+    name: &Ident,
+    params: TokenStream,
+    predicates: &[TokenStream],
+    body: impl FnOnce(&TokenStream) -> TokenStream,
+) -> TokenStream {
+    let where_clause = (!predicates.is_empty()).then(|| quote!(where #(#predicates,)*));
+    match pattern {
+        Pattern::Mutable => {
+            let body = body(&quote!(self));
+            quote! {
+                #vis fn #name(&mut self, #params) -> &mut Self
+                #where_clause
+                {
+                    #body
+                    self
+                }
+            }
+        }
+        Pattern::Owned => {
+            let body = body(&quote!(self));
+            quote! {
+                #vis fn #name(mut self, #params) -> Self
+                #where_clause
+                {
+                    #body
+                    self
+                }
+            }
+        }
+        Pattern::Immutable => {
+            let body = body(&quote!(builder));
+            quote! {
+                #vis fn #name(&self, #params) -> Self
+                where
+                    Self: ::std::clone::Clone,
+                    #(#predicates,)*
+                {
+                    let mut builder = ::std::clone::Clone::clone(self);
+                    #body
+                    builder
+                }
+            }
+        }
+    }
+}
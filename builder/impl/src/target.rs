@@ -0,0 +1,209 @@
+use crate::attr::StructAttrs;
+use crate::field::Field;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::ext::IdentExt;
+use syn::{DeriveInput, Fields, Generics, Ident, Variant, Visibility};
+
+/// Something a builder is generated for: the struct the derive is on, or one
+/// struct-like variant of an enum.
+pub struct Target<'a> {
+    pub vis: &'a Visibility,
+    /// The type returned by `build()`.
+    pub ty: &'a Ident,
+    pub generics: &'a Generics,
+    pub attrs: &'a StructAttrs,
+    /// Path naming the struct or variant in the struct expression built by
+    /// `build()`.
+    pub ctor: TokenStream,
+    /// Associated function of `ty` that returns an empty builder.
+    pub builder_fn: Ident,
+    pub builder: Ident,
+    pub fields: Vec<Field<'a>>,
+    /// Whether this is a variant of an enum rather than a struct.
+    variant: bool,
+}
+
+impl<'a> Target<'a> {
+    pub fn from_struct(
+        input: &'a DeriveInput,
+        attrs: &'a StructAttrs,
+        fields: &'a Fields,
+    ) -> syn::Result<Self> {
+        let ty = &input.ident;
+        Ok(Target {
+            vis: &input.vis,
+            ty,
+            generics: &input.generics,
+            attrs,
+            ctor: quote!(#ty),
+            builder_fn: format_ident!("builder"),
+            builder: format_ident!("{}Builder", ty),
+            fields: Field::parse_all(fields, attrs)?,
+            variant: false,
+        })
+    }
+
+    pub fn from_variant(
+        input: &'a DeriveInput,
+        attrs: &'a StructAttrs,
+        variant: &'a Variant,
+    ) -> syn::Result<Self> {
+        let ty = &input.ident;
+        let ident = &variant.ident;
+        Ok(Target {
+            vis: &input.vis,
+            ty,
+            generics: &input.generics,
+            attrs,
+            ctor: quote!(#ty::#ident),
+            builder_fn: format_ident!("{}_builder", snake_case(ident)),
+            builder: format_ident!("{}{}Builder", ty, ident),
+            fields: Field::parse_all(&variant.fields, attrs)?,
+            variant: true,
+        })
+    }
+
+    /// How docs refer to the struct or variant being built.
+    pub fn doc_name(&self) -> String {
+        self.ctor.to_string().replace(' ', "")
+    }
+
+    /// Doc attributes for the builder struct, listing the fields that have to
+    /// be set before building.
+    pub fn builder_doc(&self) -> TokenStream {
+        let ty = self.ty;
+        let summary = if self.variant {
+            format!(
+                " Builder for the [`{}`] variant, created by [`{}::{}()`].",
+                self.doc_name(),
+                ty,
+                self.builder_fn,
+            )
+        } else {
+            format!(
+                " Builder for [`{}`], created by [`{}::{}()`].",
+                ty, ty, self.builder_fn
+            )
+        };
+        let required = self
+            .fields
+            .iter()
+            .filter(|f| f.is_required())
+            .map(|f| format!("`{}`", f.setter.as_ref().unwrap_or(&f.ident).unraw()))
+            .collect::<Vec<_>>();
+        let required = if required.is_empty() {
+            " No field has to be set before building.".to_owned()
+        } else {
+            format!(" Required fields: {}.", required.join(", "))
+        };
+        quote! {
+            #[doc = #summary]
+            #[doc = ""]
+            #[doc = #required]
+        }
+    }
+
+    /// Doc attribute for the associated function returning an empty builder.
+    pub fn builder_fn_doc(&self) -> TokenStream {
+        let doc = format!(" Creates an empty [`{}`].", self.builder);
+        quote!(#[doc = #doc])
+    }
+
+    /// Declaration of the builder's marker field, if it needs one: a variant
+    /// may not mention every generic parameter of its enum.
+    pub fn phantom_field(&self) -> TokenStream {
+        if !self.variant {
+            return TokenStream::new();
+        }
+        let ty = self.ty;
+        let (_, ty_generics, _) = self.generics.split_for_impl();
+        quote! {
+            __phantom: ::std::marker::PhantomData<fn() -> #ty #ty_generics>,
+        }
+    }
+
+    /// Initializer of the builder's marker field, if it has one.
+    pub fn phantom_init(&self) -> TokenStream {
+        if !self.variant {
+            return TokenStream::new();
+        }
+        quote! {
+            __phantom: ::std::marker::PhantomData,
+        }
+    }
+
+    /// `impl From<Ty> for Builder`, starting the builder `builder_ty` off with
+    /// every field of an existing value. Only for structs, since an enum value
+    /// may be of another variant than the builder's.
+    pub fn impl_from(&self, builder_ty: TokenStream, state_init: TokenStream) -> TokenStream {
+        if self.variant {
+            return TokenStream::new();
+        }
+        let ty = self.ty;
+        let builder = &self.builder;
+        let (impl_generics, ty_generics, where_clause) = self.generics.split_for_impl();
+        let idents = self.fields.iter().map(|f| &f.ident);
+        let stored = self.fields.iter().map(|f| {
+            let member = &f.member;
+            f.stored_expr(quote!(value.#member))
+        });
+        quote! {
+            impl #impl_generics ::std::convert::From<#ty #ty_generics> for #builder_ty #where_clause {
+                fn from(value: #ty #ty_generics) -> Self {
+                    #builder {
+                        #(#idents: #stored,)*
+                        #state_init
+                    }
+                }
+            }
+        }
+    }
+
+    /// `impl derive_builder::Buildable`, for structs only: an enum has a
+    /// builder per variant rather than one for the whole type.
+    pub fn buildable(&self) -> TokenStream {
+        if self.variant {
+            return TokenStream::new();
+        }
+        let ty = self.ty;
+        let builder_fn = &self.builder_fn;
+        let builder = &self.builder;
+        let (impl_generics, ty_generics, where_clause) = self.generics.split_for_impl();
+        quote! {
+            impl #impl_generics ::derive_builder::Buildable for #ty #ty_generics #where_clause {
+                type Builder = #builder #ty_generics;
+
+                fn builder() -> Self::Builder {
+                    #ty::#builder_fn()
+                }
+            }
+        }
+    }
+}
+
+/// `RoundRect` -> `round_rect`, and `HTTPRequest` -> `http_request`: a run
+/// of capitals is one word, except for its last letter if a lowercase one
+/// follows.
+fn snake_case(ident: &Ident) -> String {
+    let chars = ident.unraw().to_string().chars().collect::<Vec<_>>();
+    let mut snake = String::new();
+    for (i, &ch) in chars.iter().enumerate() {
+        if ch.is_uppercase() {
+            let prev = i.checked_sub(1).map(|i| chars[i]);
+            let next = chars.get(i + 1);
+            let starts_word = match prev {
+                None | Some('_') => false,
+                Some(prev) if prev.is_uppercase() => next.is_some_and(|next| next.is_lowercase()),
+                Some(_) => true,
+            };
+            if starts_word {
+                snake.push('_');
+            }
+            snake.extend(ch.to_lowercase());
+        } else {
+            snake.push(ch);
+        }
+    }
+    snake
+}
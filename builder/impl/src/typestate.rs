@@ -0,0 +1,228 @@
+//! Expansion for `#[builder(typestate)]`.
+//!
+//! The builder gets one type parameter per required field which is either the
+//! `...BuilderUnset` or the `...BuilderSet` marker. Setters take the builder by
+//! value so that they can change that parameter, and `build()` is only
+//! implemented once every parameter is `...BuilderSet`, which makes it
+//! infallible.
+
+use crate::attr::Pattern;
+use crate::error::BuilderError;
+use crate::field::{camel_case, Field};
+use crate::setter;
+use crate::target::Target;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::ext::IdentExt;
+use syn::{parse_quote, GenericParam, Generics, Ident};
+
+pub fn expand(target: &Target) -> TokenStream {
+    let vis = target.vis;
+    let ty = target.ty;
+    let ctor = &target.ctor;
+    let builder_fn = &target.builder_fn;
+    let builder = &target.builder;
+    let fields = &target.fields;
+    let set = format_ident!("{}Set", builder);
+    let unset = format_ident!("{}Unset", builder);
+    let error = BuilderError::new(target);
+    let phantom_field = target.phantom_field();
+    let phantom_init = target.phantom_init();
+    let builder_doc = target.builder_doc();
+    let builder_fn_doc = target.builder_fn_doc();
+    let build_doc = format!(
+        " Builds [`{}`], which is only possible once every required field is set.",
+        target.doc_name(),
+    );
+    let set_doc = format!(" Marks a required field of [`{}`] as set.", builder);
+    let unset_doc = format!(" Marks a required field of [`{}`] as not set yet.", builder);
+
+    let params = fields
+        .iter()
+        .filter(|f| f.is_required())
+        .map(|f| state_param(&f.ident))
+        .collect::<Vec<_>>();
+    let all_set = params.iter().map(|_| &set).collect::<Vec<_>>();
+
+    // The struct's own generics followed by the state parameters.
+    let args = generic_args(target.generics);
+    let mut generics = target.generics.clone();
+    generics.params.extend(
+        params
+            .iter()
+            .map(|param| -> GenericParam { parse_quote!(#param = #unset) }),
+    );
+    let (impl_generics, ty_generics, where_clause) = target.generics.split_for_impl();
+    let (state_impl_generics, state_ty_generics, _) = generics.split_for_impl();
+
+    let idents = fields.iter().map(|f| &f.ident).collect::<Vec<_>>();
+    let members = fields.iter().map(|f| &f.member).collect::<Vec<_>>();
+    let storage = fields.iter().map(Field::storage_ty);
+    let inits = fields.iter().map(|f| {
+        let ident = &f.ident;
+        f.build_expr(quote!(self.#ident), &error)
+    });
+
+    let setters = fields.iter().map(|field| {
+        if !field.is_required() {
+            return setter::setters(field, Pattern::Owned);
+        }
+
+        let ident = &field.ident;
+        let vis = &field.vis;
+        let setter = field.setter.as_ref().unwrap();
+        let this = state_param(ident);
+        let state = params.iter().map(|param| {
+            if *param == this {
+                quote!(#set)
+            } else {
+                quote!(#param)
+            }
+        });
+        let others = idents
+            .iter()
+            .filter(|other| **other != ident)
+            .collect::<Vec<_>>();
+        let (param, value) = field.setter_arg(ident, field.storage_ty());
+        let doc = field.setter_doc(&format!("Sets `{}`.", ident.unraw()));
+        quote! {
+            #doc
+            #vis fn #setter(self, #ident: #param) -> #builder<#(#args,)* #(#state),*> {
+                #builder {
+                    #ident: ::std::option::Option::Some(#value),
+                    #(#others: self.#others,)*
+                    __state: ::std::marker::PhantomData,
+                    #phantom_init
+                }
+            }
+        }
+    });
+    let setters = setters.collect::<Vec<_>>();
+    // Clearing a required field would have to change the builder's type.
+    let state_methods = fields
+        .iter()
+        .filter(|f| !f.is_required())
+        .map(|f| setter::state_methods(f, Pattern::Owned));
+    let impl_from = target.impl_from(
+        quote!(#builder<#(#args,)* #(#all_set,)*>),
+        quote! {
+            __state: ::std::marker::PhantomData,
+            #phantom_init
+        },
+    );
+
+    // Nothing can go missing, so `build()` only needs to return a Result if
+    // there is a `validate` hook that may reject the value.
+    let (build, ops, error_enum) = if error.is_fallible() {
+        let error_ident = &error.ident;
+        let validate = error.validate(&format_ident!("value"));
+        let build = quote! {
+            #[doc = #build_doc]
+            pub fn build(self) -> ::std::result::Result<#ty #ty_generics, #error_ident> {
+                let value = #ctor {
+                    #(#members: #inits,)*
+                };
+                #validate
+                ::std::result::Result::Ok(value)
+            }
+        };
+        let ops = quote! {
+            type Error = #error_ident;
+
+            fn build(self) -> ::std::result::Result<#ty #ty_generics, #error_ident> {
+                Self::build(self)
+            }
+        };
+        (build, ops, error.expand())
+    } else {
+        let build = quote! {
+            #[doc = #build_doc]
+            pub fn build(self) -> #ty #ty_generics {
+                #ctor {
+                    #(#members: #inits,)*
+                }
+            }
+        };
+        let ops = quote! {
+            type Error = ::std::convert::Infallible;
+
+            fn build(self) -> ::std::result::Result<#ty #ty_generics, ::std::convert::Infallible> {
+                ::std::result::Result::Ok(Self::build(self))
+            }
+        };
+        (build, ops, TokenStream::new())
+    };
+
+    quote! {
+        #[doc = #set_doc]
+        #vis struct #set;
+
+        #[doc = #unset_doc]
+        #vis struct #unset;
+
+        impl #impl_generics #ty #ty_generics #where_clause {
+            #builder_fn_doc
+            pub fn #builder_fn() -> #builder #ty_generics {
+                #builder {
+                    #(#idents: ::std::option::Option::None,)*
+                    __state: ::std::marker::PhantomData,
+                    #phantom_init
+                }
+            }
+        }
+
+        #builder_doc
+        #vis struct #builder #generics #where_clause {
+            #(#idents: ::std::option::Option<#storage>,)*
+            __state: ::std::marker::PhantomData<(#(#params,)*)>,
+            #phantom_field
+        }
+
+        impl #state_impl_generics #builder #state_ty_generics #where_clause {
+            #(#setters)*
+
+            #(#state_methods)*
+        }
+
+        #impl_from
+
+        impl #impl_generics #builder<#(#args,)* #(#all_set,)*> #where_clause {
+            #build
+        }
+
+        impl #impl_generics ::derive_builder::BuilderOps for #builder<#(#args,)* #(#all_set,)*>
+        #where_clause
+        {
+            type Target = #ty #ty_generics;
+            #ops
+        }
+
+        #error_enum
+    }
+}
+
+fn state_param(field: &Ident) -> Ident {
+    format_ident!("__{}", camel_case(field))
+}
+
+/// The parameters of `generics` as they are written when naming the type.
+fn generic_args(generics: &Generics) -> Vec<TokenStream> {
+    generics
+        .params
+        .iter()
+        .map(|param| match param {
+            GenericParam::Lifetime(param) => {
+                let lifetime = &param.lifetime;
+                quote!(#lifetime)
+            }
+            GenericParam::Type(param) => {
+                let ident = &param.ident;
+                quote!(#ident)
+            }
+            GenericParam::Const(param) => {
+                let ident = &param.ident;
+                quote!(#ident)
+            }
+        })
+        .collect()
+}
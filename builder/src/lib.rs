@@ -1,8 +1,58 @@
-use proc_macro::TokenStream;
+// Crates that have the "proc-macro" crate type are only allowed to export
+// procedural macros, so the traits and types used by the expansion are defined
+// in this crate, while the derive macro itself lives in derive_builder-impl and
+// is re-exported from here. Users only need to depend on derive_builder.
+//
+// The crate does not need std, so that builders can be derived in `no_std`
+// crates too (see `#[builder(no_std)]`). It only needs `alloc` for
+// `MissingFields` and `BuildPartial`, behind the default `alloc` feature.
 
-#[proc_macro_derive(Builder)]
-pub fn derive(input: TokenStream) -> TokenStream {
-    let _ = input;
+#![no_std]
 
-    unimplemented!()
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "alloc")]
+mod missing;
+
+#[cfg(feature = "alloc")]
+pub use crate::missing::{BuildPartial, MissingFields};
+pub use derive_builder_impl::Builder;
+
+/// A type with a builder derived by `#[derive(Builder)]`, for code that is
+/// generic over such types:
+///
+/// ```
+/// use derive_builder::{Buildable, BuilderOps};
+///
+/// fn load<T>() -> Result<T, <T::Builder as BuilderOps>::Error>
+/// where
+///     T: Buildable,
+///     T::Builder: BuilderOps<Target = T>,
+/// {
+///     T::builder().build()
+/// }
+/// ```
+///
+/// Enums get one builder per variant and so do not implement it.
+pub trait Buildable {
+    type Builder;
+
+    /// The empty builder, as from the inherent `builder()` function.
+    fn builder() -> Self::Builder;
+}
+
+/// The `build()` method of a derived builder.
+///
+/// Builders are taken by value whatever their pattern. Typestate builders
+/// only implement this once all required fields are set, and as they cannot
+/// fail for a missing field their `Error` is `Infallible` unless they have a
+/// `validate` hook or sub-builders. Const builders, whose own `build` panics
+/// on a missing field, report it here through their error enum instead.
+pub trait BuilderOps {
+    /// The type being built.
+    type Target;
+    type Error;
+
+    fn build(self) -> Result<Self::Target, Self::Error>;
 }
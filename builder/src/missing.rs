@@ -0,0 +1,85 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::{self, Display};
+
+/// The `build_partial()` method of a derived builder, through which a builder
+/// builds the sub-builders it holds.
+///
+/// Only builders that have the method implement it, which are those whose
+/// `build()` can only fail for a missing field.
+pub trait BuildPartial {
+    /// The type being built.
+    type Target;
+
+    fn build_partial(&self) -> Result<Self::Target, MissingFields>;
+}
+
+/// The required fields that are not set, as reported by the `build_partial()`
+/// method of a derived builder.
+///
+/// It converts from the error of a builder whose `build()` can only fail for
+/// a missing field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MissingFields {
+    fields: Vec<&'static str>,
+    nested: Vec<(&'static str, MissingFields)>,
+}
+
+impl MissingFields {
+    /// Called by the expansion with the names of the fields, of which there
+    /// is at least one, and the missing fields of those that are sub-builders.
+    pub fn new(fields: Vec<&'static str>, nested: Vec<(&'static str, MissingFields)>) -> Self {
+        MissingFields { fields, nested }
+    }
+
+    /// The names of the fields, in declaration order, as `missing_fields()`
+    /// lists them. A sub-builder is listed if it has a missing field itself.
+    pub fn fields(&self) -> &[&'static str] {
+        &self.fields
+    }
+
+    /// The missing fields of the sub-builder `field`, if it is listed.
+    pub fn nested(&self, field: &str) -> Option<&MissingFields> {
+        self.nested
+            .iter()
+            .find(|(name, _)| *name == field)
+            .map(|(_, missing)| missing)
+    }
+
+    /// Every missing field as a path through the sub-builders, like
+    /// `server.host`.
+    pub fn paths(&self) -> Vec<String> {
+        let mut paths = Vec::new();
+        for &field in &self.fields {
+            match self.nested(field) {
+                Some(nested) => paths.extend(
+                    nested
+                        .paths()
+                        .into_iter()
+                        .map(|path| format!("{}.{}", field, path)),
+                ),
+                None => paths.push(String::from(field)),
+            }
+        }
+        paths
+    }
+}
+
+impl Display for MissingFields {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let paths = self.paths();
+        let plural = if paths.len() == 1 { "" } else { "s" };
+        write!(f, "field{} ", plural)?;
+        for (i, path) in paths.iter().enumerate() {
+            if i != 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "`{}`", path)?;
+        }
+        let verb = if paths.len() == 1 { "is" } else { "are" };
+        write!(f, " {} not set", verb)
+    }
+}
+
+impl core::error::Error for MissingFields {}
@@ -0,0 +1,58 @@
+// With #[builder(typestate)] on the struct, the builder tracks in its type
+// which of the required fields have been set. Setters take and return the
+// builder by value, and `build` only exists once every required field has been
+// given a value. Because of that it cannot fail and returns the struct itself
+// rather than a Result.
+//
+//     pub struct CommandBuilder<
+//         __Executable = CommandBuilderUnset,
+//         __Env = CommandBuilderUnset,
+//     > {
+//         ...
+//     }
+//
+//     impl CommandBuilder<CommandBuilderSet, CommandBuilderSet> {
+//         pub fn build(self) -> Command {
+//             ...
+//         }
+//     }
+//
+// Optional and repeated fields do not get a type parameter; they may be set in
+// any state, or not at all.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    env: Vec<String>,
+    current_dir: Option<String>,
+}
+
+fn main() {
+    let command = Command::builder()
+        .arg("build".to_owned())
+        .env(vec![])
+        .executable("cargo".to_owned())
+        .arg("--release".to_owned())
+        .build();
+
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.args, vec!["build", "--release"]);
+    assert!(command.env.is_empty());
+    assert!(command.current_dir.is_none());
+
+    // Setting a required field twice keeps the builder buildable.
+    let command = Command::builder()
+        .executable("rustc".to_owned())
+        .env(vec!["RUST_LOG=debug".to_owned()])
+        .executable("cargo".to_owned())
+        .current_dir("..".to_owned())
+        .build();
+
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.current_dir.as_deref(), Some(".."));
+}
@@ -0,0 +1,20 @@
+// A typestate builder that is missing a required field has no `build` method,
+// so forgetting a field is caught at compile time instead of by an unwrap at
+// runtime.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Command {
+    executable: String,
+    env: Vec<String>,
+    current_dir: Option<String>,
+}
+
+fn main() {
+    let _command = Command::builder()
+        .executable("cargo".to_owned())
+        .current_dir("..".to_owned())
+        .build();
+}
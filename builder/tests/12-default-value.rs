@@ -0,0 +1,64 @@
+// Fields marked #[builder(default)] fall back to Default::default() when their
+// setter was never called, and #[builder(default = "...")] lets the caller
+// provide any expression of the field's type instead. Either way `build` no
+// longer fails because of that field.
+//
+// The expression is only evaluated when it is needed, so it may be arbitrarily
+// expensive, and it is allowed to refer to anything in scope at the struct
+// definition.
+
+use derive_builder::Builder;
+
+fn default_dir() -> String {
+    "/".to_owned()
+}
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(default)]
+    args: Vec<String>,
+    #[builder(each = "env", default = "vec![\"TERM=xterm\".to_owned()]")]
+    env: Vec<String>,
+    #[builder(default = "default_dir()")]
+    current_dir: String,
+    #[builder(default = "Some(30)")]
+    timeout: Option<u32>,
+}
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Job {
+    name: String,
+    #[builder(default = "1")]
+    retries: u8,
+}
+
+fn main() {
+    let command = Command::builder()
+        .executable("cargo".to_owned())
+        .build()
+        .unwrap();
+
+    assert!(command.args.is_empty());
+    assert_eq!(command.env, vec!["TERM=xterm"]);
+    assert_eq!(command.current_dir, "/");
+    assert_eq!(command.timeout, Some(30));
+
+    let command = Command::builder()
+        .executable("cargo".to_owned())
+        .args(vec!["build".to_owned()])
+        .env("RUST_LOG=debug".to_owned())
+        .current_dir("..".to_owned())
+        .timeout(5)
+        .build()
+        .unwrap();
+
+    assert_eq!(command.args, vec!["build"]);
+    assert_eq!(command.env, vec!["RUST_LOG=debug"]);
+    assert_eq!(command.current_dir, "..");
+    assert_eq!(command.timeout, Some(5));
+
+    let job = Job::builder().name("nightly".to_owned()).build();
+    assert_eq!(job.retries, 1);
+}
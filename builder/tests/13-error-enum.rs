@@ -0,0 +1,50 @@
+// Instead of a Box<dyn Error>, `build` reports a missing field through an enum
+// generated next to the builder with one variant per required field. Fields
+// that are optional, repeated or have a default never go missing and so do not
+// get a variant.
+//
+//     #[derive(Debug, Clone, PartialEq, Eq)]
+//     pub enum CommandBuilderError {
+//         MissingExecutable,
+//         MissingEnv,
+//     }
+//
+// The enum implements std::error::Error, so `?` still converts it into a
+// Box<dyn Error> where that is more convenient.
+
+use derive_builder::Builder;
+use std::error::Error;
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    env: Vec<String>,
+    current_dir: Option<String>,
+    #[builder(default)]
+    timeout: u32,
+}
+
+fn build_boxed() -> Result<Command, Box<dyn Error>> {
+    let command = Command::builder().env(vec![]).build()?;
+    Ok(command)
+}
+
+fn main() {
+    let err = Command::builder()
+        .executable("cargo".to_owned())
+        .build()
+        .err()
+        .unwrap();
+    assert_eq!(err, CommandBuilderError::MissingEnv);
+    assert_eq!(err.to_string(), "field `env` is not set");
+
+    match Command::builder().build() {
+        Err(CommandBuilderError::MissingExecutable) => {}
+        _ => panic!("expected the first missing field to be reported"),
+    }
+
+    let err = build_boxed().err().unwrap();
+    assert_eq!(err.to_string(), "field `executable` is not set");
+}
@@ -0,0 +1,197 @@
+// #[builder(validate = "path::to::fn")] on the struct names a function that is
+// handed the fully assembled struct at the end of `build`. It returns a
+// Result<(), E> where E implements Display; an Err is reported by `build` as
+// the Validation variant of the builder's error type, carrying the message.
+//
+// This is the place to enforce invariants that span several fields, which the
+// individual setters cannot see.
+//
+// To match on the hook's own error rather than its message, name its type
+// with #[builder(validate_error = "Type")]. The Validation variant then
+// carries that type, which has to implement Debug and Display, and the hook
+// may return any error convertible into it with From. The builder's error is
+// Clone, PartialEq and Eq only if that type is, and so is the error of every
+// builder holding this one as a sub-builder.
+//
+// A typestate builder's `build` cannot fail otherwise, so it only returns a
+// Result when it has a validate hook.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(validate = "Command::check")]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    current_dir: Option<String>,
+}
+
+impl Command {
+    fn check(&self) -> Result<(), String> {
+        if self.executable == "cd" && !self.args.is_empty() {
+            return Err(format!("`{}` does not take arguments", self.executable));
+        }
+        Ok(())
+    }
+}
+
+fn check_job(job: &Job) -> Result<(), &'static str> {
+    if job.retries > 3 {
+        Err("too many retries")
+    } else {
+        Ok(())
+    }
+}
+
+#[derive(Builder)]
+#[builder(typestate, validate = "check_job")]
+pub struct Job {
+    name: String,
+    retries: u8,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum RangeError {
+    Empty,
+    Reversed { start: u32, end: u32 },
+}
+
+impl std::fmt::Display for RangeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            RangeError::Empty => f.write_str("empty range"),
+            RangeError::Reversed { start, end } => write!(f, "{} is after {}", start, end),
+        }
+    }
+}
+
+#[derive(Builder)]
+#[builder(validate = "Range::check", validate_error = "RangeError")]
+pub struct Range {
+    start: u32,
+    end: u32,
+}
+
+impl Range {
+    fn check(&self) -> Result<(), RangeError> {
+        if self.start == self.end {
+            Err(RangeError::Empty)
+        } else if self.start > self.end {
+            Err(RangeError::Reversed {
+                start: self.start,
+                end: self.end,
+            })
+        } else {
+            Ok(())
+        }
+    }
+}
+
+// Box<dyn Error> is neither Clone nor PartialEq.
+#[derive(Builder)]
+#[builder(
+    validate = "Port::check",
+    validate_error = "Box<dyn std::error::Error>"
+)]
+pub struct Port {
+    number: String,
+}
+
+impl Port {
+    fn check(&self) -> Result<(), std::num::ParseIntError> {
+        self.number.parse::<u16>().map(drop)
+    }
+}
+
+// Neither is the error of a builder with a Port inside.
+#[derive(Builder)]
+pub struct Service {
+    name: String,
+    #[builder(sub_builder)]
+    port: Port,
+}
+
+#[derive(Builder)]
+pub struct Window {
+    #[builder(sub_builder)]
+    range: Range,
+}
+
+fn main() {
+    let command = Command::builder()
+        .executable("cargo".to_owned())
+        .arg("build".to_owned())
+        .build()
+        .unwrap();
+    assert_eq!(command.args, vec!["build"]);
+
+    let err = Command::builder()
+        .executable("cd".to_owned())
+        .arg("..".to_owned())
+        .build()
+        .err()
+        .unwrap();
+    assert_eq!(
+        err,
+        CommandBuilderError::Validation("`cd` does not take arguments".to_owned()),
+    );
+    assert_eq!(err.to_string(), "`cd` does not take arguments");
+
+    // Missing fields are still reported before the hook runs.
+    let err = Command::builder().build().err().unwrap();
+    assert_eq!(err, CommandBuilderError::MissingExecutable);
+
+    let job = Job::builder()
+        .name("nightly".to_owned())
+        .retries(2)
+        .build()
+        .unwrap();
+    assert_eq!(job.retries, 2);
+
+    let err = Job::builder()
+        .name("nightly".to_owned())
+        .retries(5)
+        .build()
+        .err()
+        .unwrap();
+    assert_eq!(err, JobBuilderError::Validation("too many retries".to_owned()));
+
+    let err = Range::builder().start(3).end(1).build().err().unwrap();
+    assert_eq!(
+        err,
+        RangeBuilderError::Validation(RangeError::Reversed { start: 3, end: 1 }),
+    );
+    assert_eq!(err.to_string(), "3 is after 1");
+    let err = Range::builder().start(1).end(1).build().err().unwrap();
+    assert!(matches!(err, RangeBuilderError::Validation(RangeError::Empty)));
+
+    let err = Port::builder().number("http".to_owned()).build().err().unwrap();
+    let PortBuilderError::Validation(err) = err else {
+        panic!("expected a validation error");
+    };
+    assert!(err.is::<std::num::ParseIntError>());
+
+    let err = Service::builder()
+        .name("web".to_owned())
+        .port(|port| port.number("http".to_owned()))
+        .build()
+        .err()
+        .unwrap();
+    assert!(matches!(
+        err,
+        ServiceBuilderError::NestedPort(PortBuilderError::Validation(_)),
+    ));
+
+    let err = Window::builder()
+        .range(|range| range.start(3).end(1))
+        .build()
+        .err()
+        .unwrap();
+    assert_eq!(
+        err,
+        WindowBuilderError::NestedRange(RangeBuilderError::Validation(
+            RangeError::Reversed { start: 3, end: 1 },
+        )),
+    );
+}
@@ -0,0 +1,83 @@
+// The builder carries over the generic parameters of the struct it builds,
+// including lifetimes, const generics, trait bounds and the where-clause, so
+// that setters can take the field types exactly as they are written.
+//
+//     pub struct RecordBuilder<'a, T: Display, const N: usize>
+//     where
+//         T: Clone,
+//     {
+//         name: Option<&'a str>,
+//         value: Option<T>,
+//         ...
+//     }
+//
+// The mutable builder clones every field in `build`, so `build` is only
+// available when the field types are Clone.
+
+use derive_builder::Builder;
+use std::fmt::Display;
+
+pub trait Storage {
+    fn load(&self) -> String;
+}
+
+#[derive(Clone)]
+pub struct Memory(&'static str);
+
+impl Storage for Memory {
+    fn load(&self) -> String {
+        self.0.to_owned()
+    }
+}
+
+#[derive(Builder)]
+pub struct Record<'a, T: Display, const N: usize>
+where
+    T: Clone,
+{
+    name: &'a str,
+    value: T,
+    checksum: [u8; N],
+    previous: Option<T>,
+    #[builder(each = "tag")]
+    tags: Vec<&'a str>,
+}
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Service<S: Storage, U = u16> {
+    storage: S,
+    port: U,
+    #[builder(default)]
+    verbose: bool,
+}
+
+fn main() {
+    let name = String::from("answer");
+    let record = Record::builder()
+        .name(&name)
+        .value(42)
+        .checksum([1, 2, 3])
+        .tag("a")
+        .tag("b")
+        .build()
+        .unwrap();
+
+    assert_eq!(record.name, "answer");
+    assert_eq!(record.value.to_string(), "42");
+    assert_eq!(record.checksum.len(), 3);
+    assert!(record.previous.is_none());
+    assert_eq!(record.tags, vec!["a", "b"]);
+
+    let err = Record::<u8, 0>::builder().build().err().unwrap();
+    assert_eq!(err, RecordBuilderError::MissingName);
+
+    let service: Service<Memory> = Service::builder()
+        .port(8080)
+        .storage(Memory("config"))
+        .build();
+
+    assert_eq!(service.storage.load(), "config");
+    assert_eq!(service.port, 8080);
+    assert!(!service.verbose);
+}
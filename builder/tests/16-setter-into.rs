@@ -0,0 +1,57 @@
+// #[builder(setter(into))] makes a setter generic over anything that converts
+// into the field type, which saves the caller from writing `.to_owned()` at
+// every call site:
+//
+//     pub fn executable(&mut self, executable: impl Into<String>) -> &mut Self
+//
+// On a field it applies to that field's setters, including the one generated by
+// `each`. On the struct it applies to every field.
+
+use derive_builder::Builder;
+use std::path::PathBuf;
+
+#[derive(Builder)]
+pub struct Command {
+    #[builder(setter(into))]
+    executable: String,
+    #[builder(each = "arg", setter(into))]
+    args: Vec<String>,
+    env: Vec<String>,
+    #[builder(setter(into))]
+    current_dir: Option<PathBuf>,
+}
+
+#[derive(Builder)]
+#[builder(setter(into), typestate)]
+pub struct Job {
+    name: String,
+    #[builder(each = "step")]
+    steps: Vec<String>,
+    owner: Option<String>,
+}
+
+fn main() {
+    let command = Command::builder()
+        .executable("cargo")
+        .arg("build")
+        .arg(String::from("--release"))
+        .env(vec![])
+        .current_dir("..")
+        .build()
+        .unwrap();
+
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.args, vec!["build", "--release"]);
+    assert_eq!(command.current_dir, Some(PathBuf::from("..")));
+
+    let job = Job::builder()
+        .name("nightly")
+        .step("fetch")
+        .step("test")
+        .owner("ci")
+        .build();
+
+    assert_eq!(job.name, "nightly");
+    assert_eq!(job.steps, vec!["fetch", "test"]);
+    assert_eq!(job.owner.as_deref(), Some("ci"));
+}
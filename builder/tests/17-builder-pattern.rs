@@ -0,0 +1,72 @@
+// #[builder(pattern = "...")] on the struct selects how setters and `build`
+// receive the builder.
+//
+//   - "mutable", the default, takes and returns `&mut Self` as in the earlier
+//     tests, and `build(&mut self)` has to clone every field.
+//
+//   - "owned" takes and returns the builder by value. `build(self)` moves the
+//     fields out instead of cloning them, so the field types need not be Clone,
+//     and the builder is easy to store in a struct or return from a function.
+//
+//   - "immutable" takes `&self` and returns a modified clone of the builder, so
+//     a partially filled builder can serve as a template for several others.
+
+use derive_builder::Builder;
+
+// Deliberately not Clone.
+pub struct Connection {
+    url: String,
+}
+
+#[derive(Builder)]
+#[builder(pattern = "owned")]
+pub struct Client {
+    connection: Connection,
+    #[builder(each = "header")]
+    headers: Vec<String>,
+    timeout: Option<u32>,
+}
+
+pub struct Config {
+    client: ClientBuilder,
+}
+
+fn defaults() -> ClientBuilder {
+    Client::builder().header("Accept: */*".to_owned()).timeout(30)
+}
+
+#[derive(Builder)]
+#[builder(pattern = "immutable")]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+}
+
+fn main() {
+    let config = Config {
+        client: defaults(),
+    };
+    let client = config
+        .client
+        .connection(Connection {
+            url: "https://example.com".to_owned(),
+        })
+        .build()
+        .unwrap();
+
+    assert_eq!(client.connection.url, "https://example.com");
+    assert_eq!(client.headers, vec!["Accept: */*"]);
+    assert_eq!(client.timeout, Some(30));
+
+    let err = Client::builder().build().err().unwrap();
+    assert_eq!(err, ClientBuilderError::MissingConnection);
+
+    let cargo = Command::builder().executable("cargo".to_owned());
+    let build = cargo.arg("build".to_owned()).build().unwrap();
+    let test = cargo.arg("test".to_owned()).build().unwrap();
+
+    assert_eq!(build.args, vec!["build"]);
+    assert_eq!(test.args, vec!["test"]);
+    assert!(cargo.build().unwrap().args.is_empty());
+}
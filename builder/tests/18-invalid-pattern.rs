@@ -0,0 +1,19 @@
+// The builder pattern must be one of the supported ones, and a typestate
+// builder has to change its type in every required setter, which only works
+// when the builder is taken by value.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(pattern = "borrowed")]
+pub struct Command {
+    executable: String,
+}
+
+#[derive(Builder)]
+#[builder(typestate, pattern = "mutable")]
+pub struct Job {
+    name: String,
+}
+
+fn main() {}
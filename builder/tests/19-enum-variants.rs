@@ -0,0 +1,98 @@
+// On an enum, the derive generates one builder per struct-like variant. The
+// builder of variant `Circle` of enum `Shape` is called `ShapeCircleBuilder`,
+// it is created by `Shape::circle_builder()`, and its `build` returns a
+// `Shape`. Everything else works exactly as for structs: Option fields are
+// optional, `each` collects elements one at a time, and errors are reported
+// through `ShapeCircleBuilderError`.
+//
+// A run of capitals in a variant's name counts as one word, so variant
+// `HTTPRequest` is built from `http_request_builder()`.
+//
+// Options on the enum itself apply to the builders of all variants. Unit and
+// tuple variants are easy enough to construct directly and get no builder.
+
+use derive_builder::Builder;
+
+#[derive(Debug, PartialEq, Builder)]
+pub enum Shape {
+    Circle {
+        radius: f64,
+        center: Option<(f64, f64)>,
+    },
+    Rect {
+        width: f64,
+        height: f64,
+        #[builder(each = "label")]
+        labels: Vec<String>,
+    },
+    Point(f64, f64),
+    Empty,
+}
+
+// A variant's builder works even if the variant does not use every generic
+// parameter of the enum.
+#[derive(Builder)]
+#[builder(pattern = "owned", setter(into))]
+pub enum Message<T> {
+    Ping { id: u32 },
+    Data { id: u32, payload: T },
+}
+
+#[derive(Builder)]
+pub enum Request {
+    HTTPRequest { url: String },
+    GetURL { url: String },
+}
+
+fn main() {
+    let circle = Shape::circle_builder().radius(1.5).build().unwrap();
+    assert_eq!(
+        circle,
+        Shape::Circle {
+            radius: 1.5,
+            center: None,
+        },
+    );
+
+    let rect = Shape::rect_builder()
+        .width(2.0)
+        .height(3.0)
+        .label("door".to_owned())
+        .build()
+        .unwrap();
+    assert_eq!(
+        rect,
+        Shape::Rect {
+            width: 2.0,
+            height: 3.0,
+            labels: vec!["door".to_owned()],
+        },
+    );
+
+    let err = Shape::rect_builder().width(2.0).build().err().unwrap();
+    assert_eq!(err, ShapeRectBuilderError::MissingHeight);
+
+    let ping = Message::<String>::ping_builder().id(7u8).build().unwrap();
+    assert!(matches!(ping, Message::Ping { id: 7 }));
+
+    let data = Message::data_builder()
+        .id(8u8)
+        .payload("hello")
+        .build()
+        .unwrap();
+    match data {
+        Message::<&str>::Data { id, payload } => {
+            assert_eq!(id, 8);
+            assert_eq!(payload, "hello");
+        }
+        Message::Ping { .. } => panic!("expected a Data message"),
+    }
+
+    let request = Request::http_request_builder()
+        .url("/".to_owned())
+        .build()
+        .unwrap();
+    assert!(matches!(request, Request::HTTPRequest { .. }));
+    let request = Request::get_url_builder().url("/".to_owned()).build().unwrap();
+    assert!(matches!(request, Request::GetURL { .. }));
+}
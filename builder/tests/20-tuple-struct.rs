@@ -0,0 +1,44 @@
+// Tuple structs get a builder too. Their fields have no names, so the setters
+// and the builder's fields are named after the position of the field, `_0`,
+// `_1` and so on, unless a field is given a name with #[builder(name = "...")].
+//
+//     impl PointBuilder {
+//         pub fn x(&mut self, x: i32) -> &mut Self { ... }
+//         pub fn y(&mut self, y: i32) -> &mut Self { ... }
+//     }
+
+use derive_builder::Builder;
+
+#[derive(Debug, PartialEq, Builder)]
+pub struct Point(#[builder(name = "x")] i32, #[builder(name = "y")] i32);
+
+#[derive(Debug, PartialEq, Builder)]
+#[builder(setter(into))]
+pub struct Label(String, Option<u8>, #[builder(each = "tag")] Vec<String>);
+
+#[derive(Debug, PartialEq, Builder)]
+#[builder(typestate)]
+pub struct Meters(f64);
+
+fn main() {
+    let point = Point::builder().x(1).y(2).build().unwrap();
+    assert_eq!(point, Point(1, 2));
+
+    let err = Point::builder().x(1).build().err().unwrap();
+    assert_eq!(err, PointBuilderError::MissingY);
+
+    let label = Label::builder()
+        ._0("exit")
+        .tag("red")
+        .tag("bold")
+        .build()
+        .unwrap();
+    assert_eq!(
+        label,
+        Label("exit".to_owned(), None, vec!["red".to_owned(), "bold".to_owned()]),
+    );
+
+    assert_eq!(Label::builder().build().err(), Some(LabelBuilderError::Missing0));
+
+    assert_eq!(Meters::builder()._0(3.5).build(), Meters(3.5));
+}
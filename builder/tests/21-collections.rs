@@ -0,0 +1,89 @@
+// `each` is not limited to Vec. It works for any collection that implements
+// Default and Extend, such as VecDeque, HashSet or BTreeSet. For maps, that is
+// types whose name ends in `Map` such as HashMap and BTreeMap, the
+// one-at-a-time setter takes a key and a value.
+//
+//     pub fn env(&mut self, key: String, value: String) -> &mut Self
+//
+// Next to it, every `each` field gets an `extend_<field>` setter that adds all
+// elements of an iterator at once.
+//
+//     pub fn extend_env(
+//         &mut self,
+//         iter: impl IntoIterator<Item = (String, String)>,
+//     ) -> &mut Self
+//
+// The element type is read off the collection's type arguments. A collection
+// without any, like String, names it with the long form of the attribute:
+//
+//     #[builder(each(name = "ch", item = "char"))]
+//     banner: String,
+//
+// `merge` appends such a collection to another of its type, which String
+// supports through Extend<String>.
+
+use derive_builder::Builder;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg", setter(into))]
+    args: Vec<String>,
+    #[builder(each = "env", setter(into))]
+    env: HashMap<String, String>,
+    #[builder(each = "limit")]
+    limits: BTreeMap<&'static str, u64>,
+    #[builder(each = "feature")]
+    features: HashSet<&'static str>,
+    #[builder(each = "step")]
+    steps: VecDeque<u8>,
+    #[builder(each = "tag")]
+    tags: BTreeSet<char>,
+    #[builder(each(name = "ch", item = "char"))]
+    banner: String,
+}
+
+fn main() {
+    let command = Command::builder()
+        .executable("cargo".to_owned())
+        .arg("build")
+        .extend_args(vec!["--release".to_owned(), "--locked".to_owned()])
+        .env("RUST_LOG", "debug")
+        .extend_env([("TERM".to_owned(), "xterm".to_owned())])
+        .limit("memory", 512)
+        .feature("serde")
+        .feature("serde")
+        .step(2)
+        .extend_steps(3..5)
+        .extend_tags("cba".chars())
+        .ch('o')
+        .extend_banner(['k', '!'])
+        .build()
+        .unwrap();
+
+    assert_eq!(command.args, vec!["build", "--release", "--locked"]);
+    assert_eq!(command.env.len(), 2);
+    assert_eq!(command.env["RUST_LOG"], "debug");
+    assert_eq!(command.env["TERM"], "xterm");
+    assert_eq!(command.limits.get("memory"), Some(&512));
+    assert_eq!(command.features.len(), 1);
+    assert_eq!(command.steps, VecDeque::from(vec![2, 3, 4]));
+    assert_eq!(command.tags.into_iter().collect::<String>(), "abc");
+    assert_eq!(command.banner, "ok!");
+
+    let command = Command::builder()
+        .executable("cargo".to_owned())
+        .build()
+        .unwrap();
+    assert!(command.env.is_empty());
+    assert!(command.tags.is_empty());
+
+    let mut builder = Command::builder();
+    builder.ch('o');
+    let mut other = Command::builder();
+    other.ch('k');
+    builder.merge(other);
+    let command = builder.executable("cargo".to_owned()).build().unwrap();
+    assert_eq!(command.banner, "ok");
+}
@@ -0,0 +1,145 @@
+// A field whose type derives Builder itself can be marked
+// #[builder(sub_builder)]. The outer builder then keeps the field's builder
+// instead of a finished value, and offers two ways to fill it in: a setter
+// that passes the sub-builder to a closure, and an accessor returning it.
+//
+//     impl ConfigBuilder {
+//         pub fn server(
+//             &mut self,
+//             f: impl FnOnce(&mut ServerBuilder) -> &mut ServerBuilder,
+//         ) -> &mut Self { ... }
+//
+//         pub fn server_mut(&mut self) -> &mut ServerBuilder { ... }
+//     }
+//
+// The outer `build` builds the sub-builder, starting from an empty one if it
+// was never touched, and reports its errors wrapped in a variant named after
+// the field, e.g. `ConfigBuilderError::NestedServer(ServerBuilderError)`. The
+// field of a tuple struct is named by its position, as in
+// `ProxyBuilderError::Nested0`.
+//
+// The closure receives the sub-builder the same way the outer setters receive
+// the outer builder, so both builders need to use the same pattern.
+
+use derive_builder::Builder;
+use std::error::Error;
+
+#[derive(Builder)]
+pub struct Server {
+    host: String,
+    #[builder(default = "8080")]
+    port: u16,
+}
+
+#[derive(Builder)]
+pub struct Limits {
+    #[builder(default = "64")]
+    connections: u32,
+}
+
+#[derive(Builder)]
+pub struct Config {
+    name: String,
+    #[builder(sub_builder)]
+    server: Server,
+    #[builder(sub_builder)]
+    limits: Limits,
+}
+
+#[derive(Builder)]
+#[builder(pattern = "owned")]
+pub struct Inner {
+    value: u8,
+}
+
+#[derive(Builder)]
+#[builder(pattern = "owned")]
+pub struct Outer {
+    #[builder(sub_builder)]
+    inner: Inner,
+}
+
+#[derive(Builder)]
+#[builder(pattern = "immutable")]
+pub struct Point {
+    x: i32,
+    y: i32,
+}
+
+#[derive(Builder)]
+#[builder(pattern = "immutable")]
+pub struct Line {
+    #[builder(sub_builder)]
+    start: Point,
+    #[builder(sub_builder)]
+    end: Point,
+}
+
+#[derive(Builder)]
+pub struct Proxy(#[builder(sub_builder)] Server);
+
+fn main() {
+    let config = Config::builder()
+        .name("api".to_owned())
+        .server(|server| server.host("localhost".to_owned()))
+        .build()
+        .unwrap();
+
+    assert_eq!(config.name, "api");
+    assert_eq!(config.server.host, "localhost");
+    assert_eq!(config.server.port, 8080);
+    assert_eq!(config.limits.connections, 64);
+
+    let mut builder = Config::builder();
+    builder.name("api".to_owned());
+    builder.server_mut().host("example.com".to_owned());
+    builder.server_mut().port(443);
+    builder.limits_mut().connections(8);
+    let config = builder.build().unwrap();
+
+    assert_eq!(config.server.host, "example.com");
+    assert_eq!(config.server.port, 443);
+    assert_eq!(config.limits.connections, 8);
+
+    let err = Config::builder()
+        .name("api".to_owned())
+        .build()
+        .err()
+        .unwrap();
+    assert_eq!(
+        err,
+        ConfigBuilderError::NestedServer(ServerBuilderError::MissingHost),
+    );
+    assert_eq!(err.to_string(), "in `server`: field `host` is not set");
+    assert_eq!(
+        err.source().unwrap().to_string(),
+        "field `host` is not set",
+    );
+
+    let outer = Outer::builder()
+        .inner(|inner| inner.value(1))
+        .inner(|inner| inner.value(2))
+        .build()
+        .unwrap();
+    assert_eq!(outer.inner.value, 2);
+
+    let origin = Line::builder().start(|start| start.x(0).y(0));
+    let line = origin
+        .end(|end| end.x(3))
+        .end(|end| end.y(4))
+        .build()
+        .unwrap();
+    assert_eq!((line.start.x, line.start.y), (0, 0));
+    assert_eq!((line.end.x, line.end.y), (3, 4));
+    assert!(origin.build().is_err());
+
+    let proxy = Proxy::builder()
+        ._0(|server| server.host("localhost".to_owned()))
+        .build()
+        .unwrap();
+    assert_eq!(proxy.0.host, "localhost");
+    assert_eq!(
+        Proxy::builder().build().err().unwrap(),
+        ProxyBuilderError::Nested0(ServerBuilderError::MissingHost),
+    );
+}
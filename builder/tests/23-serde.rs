@@ -0,0 +1,66 @@
+// With the `serde` cargo feature enabled, #[builder(deserialize)] on the
+// struct makes its builder derive serde::Deserialize. The calling crate needs
+// to depend on serde itself. Cargo features are shared by every crate in the
+// build, so the derive stays opt-in per struct rather than requiring all field
+// types of all builders to be deserializable.
+//
+// Each field of the builder is optional, and fields missing from the input are
+// simply left unset. That allows layering configuration: deserialize a partial
+// builder from a file, apply overrides with the usual setters, and let `build`
+// report whatever required field is still missing. Every builder also
+// implements Default, which gives the same empty builder as `builder()`.
+//
+// Typestate builders cannot be deserialized, since their type has to reflect
+// which fields are set.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(deserialize)]
+pub struct Server {
+    host: String,
+    #[builder(default = "8080")]
+    port: u16,
+}
+
+#[derive(Builder)]
+#[builder(deserialize)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    current_dir: Option<String>,
+    #[builder(sub_builder)]
+    server: Server,
+}
+
+fn main() {
+    let mut builder: CommandBuilder = serde_json::from_str(
+        r#"{
+            "args": ["build", "--release"],
+            "current_dir": "..",
+            "server": { "port": 9000 }
+        }"#,
+    )
+    .unwrap();
+
+    let err = builder.build().err().unwrap();
+    assert_eq!(err, CommandBuilderError::MissingExecutable);
+
+    let command = builder
+        .executable("cargo".to_owned())
+        .arg("--locked".to_owned())
+        .server(|server| server.host("localhost".to_owned()))
+        .build()
+        .unwrap();
+
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.args, vec!["build", "--release", "--locked"]);
+    assert_eq!(command.current_dir.as_deref(), Some(".."));
+    assert_eq!(command.server.host, "localhost");
+    assert_eq!(command.server.port, 9000);
+
+    let empty: CommandBuilder = serde_json::from_str("{}").unwrap();
+    let _ = empty;
+    let _ = CommandBuilder::default();
+}
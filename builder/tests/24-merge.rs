@@ -0,0 +1,79 @@
+// Configuration often comes from several places: built-in defaults, then a
+// config file, then the environment, then the command line, each overriding
+// the one before. Generate a `merge` method that layers one builder on top of
+// another.
+//
+//     impl CommandBuilder {
+//         pub fn merge(&mut self, other: CommandBuilder) -> &mut Self { ... }
+//     }
+//
+// Every field that is set in `other` replaces the value in `self`, and fields
+// `other` leaves unset keep theirs. Collections with an `each` setter are the
+// exception: the items from `other` are appended to the ones already there.
+// Sub-builders are merged field by field in turn.
+//
+// Like the setters, `merge` takes the builder according to the struct's
+// pattern. Typestate builders carry which fields are set in their type and
+// have no `merge`.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Options {
+    #[builder(default)]
+    verbose: bool,
+    #[builder(default = "1")]
+    jobs: u32,
+}
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    current_dir: Option<String>,
+    #[builder(sub_builder)]
+    options: Options,
+}
+
+#[derive(Builder)]
+#[builder(pattern = "owned")]
+pub struct Layer {
+    name: Option<String>,
+    #[builder(each = "tag")]
+    tags: Vec<String>,
+}
+
+fn main() {
+    let mut defaults = CommandBuilder::default();
+    defaults
+        .executable("cargo".to_owned())
+        .arg("build".to_owned())
+        .options(|options| options.jobs(4));
+
+    let mut file = Command::builder();
+    file.current_dir("..".to_owned())
+        .options(|options| options.verbose(true));
+
+    let mut cli = Command::builder();
+    cli.arg("--release".to_owned())
+        .current_dir("/tmp".to_owned());
+
+    let mut builder = defaults;
+    builder.merge(file).merge(cli);
+
+    let command = builder.build().unwrap();
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.args, vec!["build", "--release"]);
+    assert_eq!(command.current_dir, Some("/tmp".to_owned()));
+    assert!(command.options.verbose);
+    assert_eq!(command.options.jobs, 4);
+
+    let layer = Layer::builder()
+        .tag("a".to_owned())
+        .merge(Layer::builder().name("top".to_owned()).tag("b".to_owned()))
+        .build()
+        .unwrap();
+    assert_eq!(layer.name, Some("top".to_owned()));
+    assert_eq!(layer.tags, vec!["a", "b"]);
+}
@@ -0,0 +1,70 @@
+// Fields can name an environment variable to be read from:
+//
+//     #[derive(Builder)]
+//     pub struct Server {
+//         #[builder(env = "APP_HOST")]
+//         host: String,
+//         #[builder(env = "APP_PORT")]
+//         port: u16,
+//     }
+//
+// `ServerBuilder::from_env()` returns a builder with each such field set to
+// the value of its variable, parsed with `FromStr`. Variables that are not
+// set leave their field unset, so the usual setters (or `merge`) can still
+// fill them in afterwards.
+//
+// A value that fails to parse is reported through the builder's error type:
+//
+//     pub enum ServerBuilderError {
+//         ...
+//         InvalidEnv {
+//             var: &'static str,
+//             message: String,
+//         },
+//     }
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Server {
+    #[builder(env = "DERIVE_BUILDER_TEST_HOST")]
+    host: String,
+    #[builder(env = "DERIVE_BUILDER_TEST_PORT")]
+    port: u16,
+    #[builder(env = "DERIVE_BUILDER_TEST_WORKERS")]
+    workers: Option<usize>,
+    #[builder(default)]
+    verbose: bool,
+}
+
+fn main() {
+    std::env::set_var("DERIVE_BUILDER_TEST_HOST", "localhost");
+    std::env::set_var("DERIVE_BUILDER_TEST_PORT", "8080");
+    std::env::remove_var("DERIVE_BUILDER_TEST_WORKERS");
+
+    let server = ServerBuilder::from_env().unwrap().build().unwrap();
+    assert_eq!(server.host, "localhost");
+    assert_eq!(server.port, 8080);
+    assert_eq!(server.workers, None);
+    assert!(!server.verbose);
+
+    std::env::set_var("DERIVE_BUILDER_TEST_WORKERS", "4");
+    let server = ServerBuilder::from_env()
+        .unwrap()
+        .port(9090)
+        .build()
+        .unwrap();
+    assert_eq!(server.port, 9090);
+    assert_eq!(server.workers, Some(4));
+
+    std::env::remove_var("DERIVE_BUILDER_TEST_HOST");
+    let err = ServerBuilder::from_env().unwrap().build().err().unwrap();
+    assert_eq!(err, ServerBuilderError::MissingHost);
+
+    std::env::set_var("DERIVE_BUILDER_TEST_PORT", "eighty");
+    let err = ServerBuilder::from_env().err().unwrap();
+    assert_eq!(
+        err.to_string(),
+        "environment variable `DERIVE_BUILDER_TEST_PORT`: invalid digit found in string",
+    );
+}
@@ -0,0 +1,65 @@
+// Each field's setter can be adjusted through `setter(...)` and `vis`:
+//
+//   - `#[builder(setter(name = "..."))]` gives the setter another name. The
+//     `extend_...` and `..._mut` methods of the field follow the new name.
+//
+//   - `#[builder(setter(skip))]` generates no setter at all. The field is
+//     always built from its default, which is `Default::default()` unless
+//     given with `#[builder(default = "...")]`.
+//
+//   - `#[builder(vis = "pub(crate)")]` sets the visibility of the field's
+//     setters, which are otherwise `pub`.
+//
+//   - `#[builder(setter(strip_option = false))]` makes the setter of an
+//     `Option<T>` field take the `Option<T>` itself, so that callers can pass
+//     along a value they only have as an `Option`.
+
+mod api {
+    use derive_builder::Builder;
+
+    #[derive(Builder)]
+    pub struct Request {
+        pub url: String,
+        #[builder(setter(name = "header"), each = "add_header")]
+        pub headers: Vec<String>,
+        #[builder(setter(skip))]
+        pub attempts: u32,
+        #[builder(default = "3", vis = "pub(crate)")]
+        pub retries: u32,
+        #[builder(setter(strip_option = false))]
+        pub timeout: Option<u64>,
+        #[builder(setter(skip), default = "Some(\"derive_builder\".to_owned())")]
+        pub user_agent: Option<String>,
+    }
+}
+
+use api::Request;
+
+fn main() {
+    let configured_timeout: Option<u64> = None;
+
+    let request = Request::builder()
+        .url("https://example.com".to_owned())
+        .header(vec!["Accept: */*".to_owned()])
+        .add_header("Connection: close".to_owned())
+        .retries(5)
+        .timeout(configured_timeout)
+        .build()
+        .unwrap();
+
+    assert_eq!(request.headers.len(), 2);
+    assert_eq!(request.attempts, 0);
+    assert_eq!(request.retries, 5);
+    assert_eq!(request.timeout, None);
+    assert_eq!(request.user_agent.as_deref(), Some("derive_builder"));
+
+    let request = Request::builder()
+        .url("https://example.com".to_owned())
+        .extend_header(vec!["Accept: */*".to_owned()])
+        .timeout(Some(30))
+        .build()
+        .unwrap();
+
+    assert_eq!(request.retries, 3);
+    assert_eq!(request.timeout, Some(30));
+}
@@ -0,0 +1,20 @@
+// A setter restricted with `vis` is not available outside of where that
+// visibility reaches, even though the builder itself is public.
+
+mod api {
+    use derive_builder::Builder;
+
+    #[derive(Builder)]
+    pub struct Request {
+        pub url: String,
+        #[builder(default = "3", vis = "pub(self)")]
+        pub retries: u32,
+    }
+}
+
+fn main() {
+    let _ = api::Request::builder()
+        .url("https://example.com".to_owned())
+        .retries(5)
+        .build();
+}
@@ -0,0 +1,27 @@
+// Rather than stopping at the first malformed attribute, the derive reports
+// every one of them, across the struct and all of its fields, in a single
+// compile. Each error points at the offending key. A key that looks like a
+// misspelling of a known one comes with a suggestion:
+//
+//     error: unknown `builder` attribute `eachh`, did you mean `each`?
+//
+// Keys that do not resemble anything known list what would have been
+// accepted instead.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(patern = "owned")]
+pub struct Command {
+    executable: String,
+    #[builder(eachh = "arg")]
+    args: Vec<String>,
+    #[builder(setter(int), defualt)]
+    env: Vec<String>,
+    #[builder(frobnicate)]
+    current_dir: Option<String>,
+    #[builder(each(iten = "char"))]
+    banner: String,
+}
+
+fn main() {}
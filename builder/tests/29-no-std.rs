@@ -0,0 +1,94 @@
+// With #[builder(no_std)] the expansion refers to `::core` and `::alloc`
+// rather than `::std`, so that builders can be derived in `#![no_std]` crates
+// such as firmware. The crate needs `extern crate alloc` for builders whose
+// error type carries a `String`, which is the case with `validate`. The error
+// type implements `core::error::Error`.
+//
+// `from_env` reads the environment through std and is not available in this
+// mode, and neither are `missing_fields` and `build_partial`, which allocate.
+//
+// This test still runs on a host, so it links std under another name; any
+// `::std::...` path left in the expansion would fail to resolve. Paths in the
+// struct's own attributes are kept as written, even through a module that
+// happens to be named `std`.
+
+#![no_std]
+
+extern crate alloc;
+extern crate std as host;
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::error::Error;
+use derive_builder::Builder;
+
+mod shim {
+    pub mod std {
+        pub fn one() -> u8 {
+            1
+        }
+    }
+}
+
+#[derive(Builder)]
+#[builder(no_std)]
+pub struct Uart {
+    #[builder(default = "115200")]
+    baud: u32,
+    parity: bool,
+    #[builder(default = "shim::std::one()")]
+    stop_bits: u8,
+}
+
+#[derive(Builder)]
+#[builder(no_std, validate = "DeviceConfig::check")]
+pub struct DeviceConfig {
+    name: String,
+    #[builder(each = "pin")]
+    pins: Vec<u8>,
+    watchdog: Option<u16>,
+    #[builder(sub_builder)]
+    uart: Uart,
+}
+
+impl DeviceConfig {
+    fn check(&self) -> Result<(), &'static str> {
+        if self.pins.len() > 4 {
+            return Err("at most 4 pins");
+        }
+        Ok(())
+    }
+}
+
+fn main() {
+    let config = DeviceConfig::builder()
+        .name("sensor".to_string())
+        .pin(2)
+        .pin(3)
+        .uart(|uart| uart.parity(true))
+        .build()
+        .unwrap();
+    assert_eq!(config.name, "sensor");
+    assert_eq!(config.pins, [2, 3]);
+    assert_eq!(config.watchdog, None);
+    assert_eq!(config.uart.baud, 115200);
+    assert!(config.uart.parity);
+    assert_eq!(config.uart.stop_bits, 1);
+
+    let err = DeviceConfig::builder()
+        .name("sensor".to_string())
+        .extend_pins([1, 2, 3, 4, 5])
+        .uart(|uart| uart.parity(false))
+        .build()
+        .err()
+        .unwrap();
+    assert_eq!(err, DeviceConfigBuilderError::Validation("at most 4 pins".to_string()));
+
+    let err = DeviceConfig::builder()
+        .name("sensor".to_string())
+        .build()
+        .err()
+        .unwrap();
+    let source = err.source().unwrap();
+    assert_eq!(source.to_string(), "field `parity` is not set");
+}
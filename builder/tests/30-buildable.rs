@@ -0,0 +1,82 @@
+// Besides the inherent `builder()` and `build()`, the derive implements two
+// traits from the derive_builder crate so that generic code can work over any
+// type with a derived builder:
+//
+//     pub trait Buildable {
+//         type Builder;
+//         fn builder() -> Self::Builder;
+//     }
+//
+//     pub trait BuilderOps {
+//         type Target;
+//         type Error;
+//         fn build(self) -> Result<Self::Target, Self::Error>;
+//     }
+//
+// `BuilderOps::build` takes the builder by value for every pattern. Typestate
+// builders implement it once all of their required fields are set, with
+// `Infallible` as the error if nothing else can go wrong. Enum variant
+// builders implement `BuilderOps` but the enum itself is not `Buildable`.
+
+use derive_builder::{Buildable, Builder, BuilderOps};
+use std::convert::Infallible;
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+}
+
+#[derive(Builder)]
+pub struct Defaults {
+    #[builder(default = "8080")]
+    port: u16,
+    host: Option<String>,
+}
+
+#[derive(Builder)]
+#[builder(pattern = "owned")]
+pub struct Owned {
+    #[builder(default)]
+    name: String,
+}
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Typed {
+    name: String,
+}
+
+fn load<T>() -> Result<T, <T::Builder as BuilderOps>::Error>
+where
+    T: Buildable,
+    T::Builder: BuilderOps<Target = T>,
+{
+    T::builder().build()
+}
+
+fn finish<B: BuilderOps>(builder: B) -> Option<B::Target> {
+    builder.build().ok()
+}
+
+fn main() {
+    let defaults: Defaults = load().unwrap();
+    assert_eq!(defaults.port, 8080);
+    assert_eq!(defaults.host, None);
+
+    let owned: Owned = load().unwrap();
+    assert_eq!(owned.name, "");
+
+    let err = load::<Command>().err().unwrap();
+    assert_eq!(err, CommandBuilderError::MissingExecutable);
+
+    let mut builder = <Command as Buildable>::builder();
+    builder.executable("cargo".to_owned()).arg("build".to_owned());
+    let command = finish(builder).unwrap();
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.args, vec!["build"]);
+
+    let typed: Result<Typed, Infallible> = BuilderOps::build(Typed::builder().name("x".to_owned()));
+    assert_eq!(typed.unwrap().name, "x");
+}
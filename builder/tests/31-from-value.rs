@@ -0,0 +1,82 @@
+// A builder can also start from an existing value, to make a modified copy of
+// it, and be inspected and reset field by field:
+//
+//     impl From<Command> for CommandBuilder { ... }
+//
+//     impl CommandBuilder {
+//         pub fn is_executable_set(&self) -> bool { ... }
+//         pub fn clear_executable(&mut self) -> &mut Self { ... }
+//         ...
+//     }
+//
+// `clear_...` takes the builder according to the struct's pattern, like the
+// setters. A sub-builder is started from the field's value in turn.
+//
+// The `From` impl of a typestate builder returns it with every required field
+// set, and only fields that are not required can be cleared, since clearing
+// a required one would have to change the builder's type.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Options {
+    #[builder(default)]
+    verbose: bool,
+}
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    current_dir: Option<String>,
+    #[builder(sub_builder)]
+    options: Options,
+}
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Typed {
+    name: String,
+    alias: Option<String>,
+}
+
+fn main() {
+    let original = Command::builder()
+        .executable("cargo".to_owned())
+        .arg("build".to_owned())
+        .current_dir("..".to_owned())
+        .options(|options| options.verbose(true))
+        .build()
+        .unwrap();
+
+    let mut builder = CommandBuilder::from(original);
+    assert!(builder.is_executable_set());
+    assert!(builder.is_current_dir_set());
+
+    builder.arg("--release".to_owned()).clear_current_dir();
+    assert!(!builder.is_current_dir_set());
+
+    let copy = builder.build().unwrap();
+    assert_eq!(copy.executable, "cargo");
+    assert_eq!(copy.args, vec!["build", "--release"]);
+    assert_eq!(copy.current_dir, None);
+    assert!(copy.options.verbose);
+
+    builder.clear_executable();
+    assert!(!builder.is_executable_set());
+    assert!(builder.build().is_err());
+
+    let mut empty = Command::builder();
+    assert!(!empty.is_args_set());
+    assert!(!empty.is_options_set());
+    empty.options(|options| options);
+    assert!(empty.is_options_set());
+
+    let typed = Typed::builder().name("x".to_owned()).alias("y".to_owned()).build();
+    let builder = TypedBuilder::from(typed).clear_alias();
+    assert!(!builder.is_alias_set());
+    let typed = builder.build();
+    assert_eq!(typed.name, "x");
+    assert_eq!(typed.alias, None);
+}
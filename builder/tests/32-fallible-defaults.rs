@@ -0,0 +1,106 @@
+// A default that has to be looked up somewhere can fail. Such a default is
+// given as a function returning a Result:
+//
+//     #[builder(default_with = "read_port")]
+//     port: u16,
+//
+//     fn read_port() -> Result<u16, impl Display>
+//
+// The function is only called if the field was never set, and its error is
+// reported through the builder's error type:
+//
+//     pub enum ServiceBuilderError {
+//         ...
+//         DefaultFailed {
+//             field: &'static str,
+//             message: String,
+//         },
+//     }
+//
+// With #[builder(async_build)] on the struct, `build` is an `async fn` and
+// fields can use #[builder(default_async = "...")] with an async function
+// returning a Result, which `build` awaits. Since `BuilderOps::build` is not
+// async, such builders do not implement `BuilderOps`.
+
+use derive_builder::Builder;
+use std::future::Future;
+use std::pin::pin;
+use std::task::{Context, Poll, Waker};
+
+#[derive(Builder)]
+pub struct Service {
+    name: String,
+    #[builder(default_with = "read_port")]
+    port: u16,
+}
+
+#[derive(Builder)]
+#[builder(async_build)]
+pub struct Remote {
+    #[builder(default_async = "resolve_address")]
+    address: String,
+    #[builder(default_with = "read_port")]
+    port: u16,
+    #[builder(default)]
+    retries: u32,
+}
+
+fn read_port() -> Result<u16, std::num::ParseIntError> {
+    std::env::var("DERIVE_BUILDER_TEST_SERVICE_PORT")
+        .unwrap_or_else(|_| "8080".to_owned())
+        .parse()
+}
+
+async fn resolve_address() -> Result<String, String> {
+    match std::env::var("DERIVE_BUILDER_TEST_REMOTE") {
+        Ok(address) => Ok(address),
+        Err(_) => Err("no remote configured".to_owned()),
+    }
+}
+
+// Everything here completes without waiting, so a single poll suffices.
+fn block_on<F: Future>(future: F) -> F::Output {
+    match pin!(future).poll(&mut Context::from_waker(Waker::noop())) {
+        Poll::Ready(output) => output,
+        Poll::Pending => panic!("future is not ready"),
+    }
+}
+
+fn main() {
+    let service = Service::builder().name("api".to_owned()).build().unwrap();
+    assert_eq!(service.port, 8080);
+
+    let service = Service::builder()
+        .name("api".to_owned())
+        .port(9000)
+        .build()
+        .unwrap();
+    assert_eq!(service.port, 9000);
+
+    std::env::set_var("DERIVE_BUILDER_TEST_SERVICE_PORT", "http");
+    let err = Service::builder()
+        .name("api".to_owned())
+        .build()
+        .err()
+        .unwrap();
+    assert_eq!(
+        err.to_string(),
+        "default of `port` failed: invalid digit found in string",
+    );
+    std::env::remove_var("DERIVE_BUILDER_TEST_SERVICE_PORT");
+
+    let err = block_on(Remote::builder().build()).err().unwrap();
+    assert_eq!(
+        err,
+        RemoteBuilderError::DefaultFailed {
+            field: "address",
+            message: "no remote configured".to_owned(),
+        },
+    );
+
+    std::env::set_var("DERIVE_BUILDER_TEST_REMOTE", "10.0.0.1");
+    let remote = block_on(Remote::builder().retries(3).build()).unwrap();
+    assert_eq!(remote.address, "10.0.0.1");
+    assert_eq!(remote.port, 8080);
+    assert_eq!(remote.retries, 3);
+}
@@ -0,0 +1,80 @@
+// With #[builder(const)] the builder can be used to initialize `const` and
+// `static` items, such as a table of processes:
+//
+//     static PROCS: [Proc; 2] = [
+//         Proc::builder().id(0).name("init").build(),
+//         Proc::builder().id(1).name("shell").priority(5).build(),
+//     ];
+//
+// `builder()`, the builder's `new()`, its setters, `is_..._set()` and `build`
+// are all `const fn`. Since a `const fn` cannot call trait methods, the
+// builder always uses the owned pattern and `build` returns the struct
+// itself rather than a Result. A required field that is missing makes
+// `build` panic, which in a const context is an error at compile time.
+//
+// For the same reason `each`, `sub_builder`, `setter(into)`, `validate` and
+// other options relying on traits are rejected, and any `default` has to be
+// given as a const expression.
+//
+// The builder still implements `BuilderOps` for generic code. That `build`
+// is not const, so it checks the fields and returns `ProcBuilderError` like a
+// regular builder would, rather than panicking.
+//
+// Setting a field drops its previous value, which a `const fn` cannot do for
+// a type with a destructor. Fields of such types, like `String` or `Vec<T>`,
+// make the derive fail with error E0493 and are not supported.
+
+use derive_builder::{Buildable, Builder, BuilderOps};
+
+#[derive(Builder)]
+#[builder(const)]
+pub struct Proc {
+    id: usize,
+    name: &'static str,
+    #[builder(default = "1")]
+    priority: u8,
+    core: Option<u8>,
+}
+
+static PROCS: [Proc; 2] = [
+    Proc::builder().id(0).name("init").build(),
+    Proc::builder()
+        .id(1)
+        .name("shell")
+        .priority(5)
+        .core(3)
+        .build(),
+];
+
+const EMPTY: ProcBuilder = ProcBuilder::new();
+const NAMED: bool = EMPTY.name("idle").is_name_set();
+
+fn load<T>() -> Result<T, <T::Builder as BuilderOps>::Error>
+where
+    T: Buildable,
+    T::Builder: BuilderOps<Target = T>,
+{
+    T::builder().build()
+}
+
+fn main() {
+    assert_eq!(PROCS[0].name, "init");
+    assert_eq!(PROCS[0].priority, 1);
+    assert_eq!(PROCS[0].core, None);
+    assert_eq!(PROCS[1].id, 1);
+    assert_eq!(PROCS[1].priority, 5);
+    assert_eq!(PROCS[1].core, Some(3));
+    assert!(NAMED);
+    assert!(!EMPTY.is_id_set());
+
+    let idle = Proc::builder().id(2).name("idle").build();
+    assert_eq!(idle.id, 2);
+
+    let err = load::<Proc>().err().unwrap();
+    assert_eq!(err, ProcBuilderError::MissingId);
+    assert_eq!(err.to_string(), "field `id` is not set");
+    let err = <ProcBuilder as BuilderOps>::build(ProcBuilder::new().id(3))
+        .err()
+        .unwrap();
+    assert_eq!(err, ProcBuilderError::MissingName);
+}
@@ -0,0 +1,17 @@
+// A required field missing from a const builder is reported when the item is
+// evaluated, which for a `const` or `static` is during compilation.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(const)]
+pub struct Proc {
+    id: usize,
+    name: &'static str,
+}
+
+static INIT: Proc = Proc::builder().id(0).build();
+
+fn main() {
+    let _ = INIT.name;
+}
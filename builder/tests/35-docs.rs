@@ -0,0 +1,94 @@
+//! Everything the derive generates is documented, so that it can be used in
+//! crates with `#![deny(missing_docs)]`.
+//!
+//! Each setter carries the doc comments of its field, followed by a note on
+//! whether the field is required, optional or repeated:
+//!
+//! ```text
+//! /// The program to run.
+//! ///
+//! /// Required: it has to be set before building.
+//! pub fn executable(&mut self, executable: String) -> &mut Self
+//! ```
+//!
+//! The builder struct says which fields have to be set before building, and
+//! the error enum, its variants, `build` and the other generated methods
+//! describe what they do.
+
+#![deny(missing_docs)]
+
+use derive_builder::Builder;
+
+/// Options of a command.
+#[derive(Builder)]
+pub struct Options {
+    /// Print what is going on.
+    #[builder(default)]
+    pub verbose: bool,
+}
+
+/// A command to run.
+#[derive(Builder)]
+#[builder(validate = "Command::check")]
+pub struct Command {
+    /// The program to run.
+    pub executable: String,
+    /// Arguments passed to the program.
+    #[builder(each = "arg")]
+    pub args: Vec<String>,
+    /// Working directory of the program.
+    pub current_dir: Option<String>,
+    /// Options of the command.
+    #[builder(sub_builder)]
+    pub options: Options,
+    /// Scheduling priority.
+    #[builder(env = "DERIVE_BUILDER_TEST_DOCS_NICE", default_with = "Command::nice")]
+    pub nice: i8,
+}
+
+impl Command {
+    fn check(&self) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn nice() -> Result<i8, String> {
+        Ok(0)
+    }
+}
+
+/// Built without any field missing.
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Typed {
+    /// A name.
+    pub name: String,
+    /// An optional alias.
+    pub alias: Option<String>,
+}
+
+/// Built in `const` context.
+#[derive(Builder)]
+#[builder(const)]
+pub struct Constant {
+    /// An identifier.
+    pub id: usize,
+}
+
+/// A shape.
+#[derive(Builder)]
+#[builder(pattern = "owned")]
+pub enum Shape {
+    /// A circle.
+    Circle {
+        /// Its radius.
+        radius: f64,
+    },
+}
+
+fn main() {
+    let command = Command::builder()
+        .executable("cargo".to_owned())
+        .build()
+        .unwrap();
+    assert_eq!(command.executable, "cargo");
+}
@@ -0,0 +1,131 @@
+// An interactive tool filling in a builder wants to ask for everything that
+// is still missing in one go, rather than learn about one field per call to
+// `build`. The builder can list them:
+//
+//     impl CommandBuilder {
+//         pub fn missing_fields(&self) -> Vec<&'static str> { ... }
+//
+//         pub fn build_partial(&self) -> Result<Command, derive_builder::MissingFields> { ... }
+//     }
+//
+// `build_partial` builds like `build` does, but borrows the builder whatever
+// its pattern, cloning the values out of it, and its error lists every
+// required field that is not set.
+//
+// A sub-builder is listed by its name if one of its own fields is missing.
+// The error also keeps what the sub-builder is missing, and displays every
+// field as a path, like `server.host`.
+//
+// `build_partial` only exists if a missing field is the only reason `build`
+// can fail. A struct with `validate` or `default_with` gets `missing_fields`
+// alone. A struct with sub-builders gets both, though `build_partial` can
+// only be called if the sub-builders have it too, through the BuildPartial
+// trait. Their errors then also convert into `MissingFields`.
+//
+// Builders derived with #[builder(no_std)] have neither method, since the
+// list of names needs an allocator. Such a builder cannot be the sub-builder
+// of one that has them. No builder has them, and there is no MissingFields,
+// with the default `alloc` feature of derive_builder turned off.
+
+use derive_builder::{Builder, MissingFields};
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    env: Vec<String>,
+    current_dir: Option<String>,
+    #[builder(default)]
+    timeout: u32,
+}
+
+#[derive(Builder)]
+pub struct Server {
+    host: String,
+    #[builder(default = "8080")]
+    port: u16,
+}
+
+#[derive(Builder)]
+pub struct Deployment {
+    name: String,
+    #[builder(sub_builder)]
+    server: Server,
+}
+
+#[derive(Builder)]
+#[builder(pattern = "owned")]
+pub struct Job {
+    name: String,
+    retries: u8,
+}
+
+#[derive(Builder)]
+pub struct Site<'a> {
+    domain: &'a str,
+    #[builder(sub_builder)]
+    server: Server,
+}
+
+fn main() {
+    let mut builder = Command::builder();
+    assert_eq!(builder.missing_fields(), vec!["executable", "env"]);
+
+    let err = builder.build_partial().err().unwrap();
+    assert_eq!(err.fields(), ["executable", "env"]);
+    assert_eq!(err.to_string(), "fields `executable`, `env` are not set");
+
+    builder.env(vec![]);
+    let err: MissingFields = builder.build_partial().err().unwrap();
+    assert_eq!(err.to_string(), "field `executable` is not set");
+
+    builder.executable("cargo".to_owned());
+    assert!(builder.missing_fields().is_empty());
+    let command = builder.build_partial().unwrap();
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.timeout, 0);
+
+    let job = Job::builder().name("nightly".to_owned());
+    let err = job.build_partial().err().unwrap();
+    assert_eq!(err.fields(), ["retries"]);
+    let job = job.retries(3);
+    assert_eq!(job.build_partial().unwrap().retries, 3);
+    assert_eq!(job.build().unwrap().name, "nightly");
+
+    let mut deployment = Deployment::builder();
+    assert_eq!(deployment.missing_fields(), vec!["name", "server"]);
+    let err = deployment.build_partial().err().unwrap();
+    assert_eq!(err.fields(), ["name", "server"]);
+    assert_eq!(err.nested("server").unwrap().fields(), ["host"]);
+    assert_eq!(err.nested("name"), None);
+    assert_eq!(err.paths(), ["name", "server.host"]);
+    assert_eq!(
+        err.to_string(),
+        "fields `name`, `server.host` are not set",
+    );
+
+    deployment.server(|server| server.port(80));
+    assert_eq!(deployment.missing_fields(), vec!["name", "server"]);
+    deployment.server(|server| server.host("localhost".to_owned()));
+    assert_eq!(deployment.missing_fields(), vec!["name"]);
+
+    deployment.name("web".to_owned());
+    let built = deployment.build_partial().unwrap();
+    assert_eq!(built.server.host, "localhost");
+    assert_eq!(built.server.port, 80);
+
+    let err = MissingFields::from(DeploymentBuilderError::NestedServer(
+        ServerBuilderError::MissingHost,
+    ));
+    assert_eq!(err.fields(), ["server"]);
+    assert_eq!(err.paths(), ["server.host"]);
+
+    let mut site = Site::builder();
+    site.domain("example.com");
+    assert_eq!(site.missing_fields(), vec!["server"]);
+    site.server(|server| server.host("localhost".to_owned()));
+    let site = site.build_partial().unwrap();
+    assert_eq!(site.domain, "example.com");
+    assert_eq!(site.server.port, 8080);
+}
@@ -0,0 +1,60 @@
+// Sometimes the value accumulated in the builder needs to be adjusted rather
+// than replaced, for example to drop an argument added earlier. Fields
+// holding a collection, either with `each` or of type Vec, get a setter that
+// hands the collection to a closure, starting from an empty one if the field
+// is not set yet:
+//
+//     pub fn args_with(&mut self, f: impl FnOnce(&mut Vec<String>)) -> &mut Self
+//
+// Option fields get a setter that only computes a value if the field is not
+// set yet, like Option::get_or_insert_with:
+//
+//     pub fn current_dir_or_insert_with(&mut self, f: impl FnOnce() -> String) -> &mut Self
+//
+// Both take the builder according to the struct's pattern, like the other
+// setters.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    env: Vec<String>,
+    current_dir: Option<String>,
+}
+
+#[derive(Builder)]
+#[builder(pattern = "owned")]
+pub struct Owned {
+    #[builder(each = "tag")]
+    tags: Vec<String>,
+    label: Option<String>,
+}
+
+fn main() {
+    let command = Command::builder()
+        .executable("cargo".to_owned())
+        .arg("build".to_owned())
+        .arg("--verbose".to_owned())
+        .args_with(|args| args.retain(|arg| arg != "--verbose"))
+        .env_with(|env| env.push("RUST_LOG=info".to_owned()))
+        .current_dir_or_insert_with(|| "..".to_owned())
+        .current_dir_or_insert_with(|| unreachable!())
+        .build()
+        .unwrap();
+
+    assert_eq!(command.args, vec!["build"]);
+    assert_eq!(command.env, vec!["RUST_LOG=info"]);
+    assert_eq!(command.current_dir, Some("..".to_owned()));
+
+    let owned = Owned::builder()
+        .tags_with(|tags| tags.push("a".to_owned()))
+        .tag("b".to_owned())
+        .label_or_insert_with(|| "default".to_owned())
+        .build()
+        .unwrap();
+    assert_eq!(owned.tags, vec!["a", "b"]);
+    assert_eq!(owned.label.as_deref(), Some("default"));
+}
@@ -0,0 +1,49 @@
+// Fields named after keywords are written as raw identifiers. The builder
+// keeps the `r#` prefix wherever the name is used as an identifier, such as
+// the setter `r#type()`, and drops it wherever the name is spelled out: in
+// error variants, error messages and the names listed by `missing_fields()`.
+//
+//     pub enum TokenBuilderError {
+//         MissingType,
+//     }
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Token {
+    r#type: String,
+    r#ref: Option<u32>,
+    #[builder(each = "r#in")]
+    r#in: Vec<u8>,
+}
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Typed {
+    r#type: String,
+}
+
+fn main() {
+    let mut builder = Token::builder();
+    assert_eq!(builder.missing_fields(), ["type"]);
+    assert!(!builder.is_type_set());
+
+    let err = builder.build().err().unwrap();
+    assert_eq!(err, TokenBuilderError::MissingType);
+    assert_eq!(err.to_string(), "field `type` is not set");
+    let err = builder.build_partial().err().unwrap();
+    assert_eq!(err.fields(), ["type"]);
+
+    let token = builder
+        .r#type("ident".to_owned())
+        .r#ref(1)
+        .r#in(2)
+        .build()
+        .unwrap();
+    assert_eq!(token.r#type, "ident");
+    assert_eq!(token.r#ref, Some(1));
+    assert_eq!(token.r#in, [2]);
+
+    let typed = Typed::builder().r#type("ident".to_owned()).build();
+    assert_eq!(typed.r#type, "ident");
+}
@@ -0,0 +1,18 @@
+// Only the enum and the fields of struct-like variants take #[builder]
+// options. An attribute on a variant itself, or on a field of a tuple
+// variant, which gets no builder, is an error rather than being ignored.
+// Malformed ones are reported as they would be anywhere else.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub enum Shape {
+    #[builder(eachh = "x")]
+    Circle {
+        radius: f64,
+    },
+    Point(#[builder(frobnicate)] f64, f64),
+    Line(#[builder(default)] f64),
+}
+
+fn main() {}
@@ -0,0 +1,34 @@
+// A const builder rejects the options it cannot honor in a `const fn` with an
+// error at the field, rather than leaving the compiler to report a call to a
+// non-const trait method inside the derive's output. Both `default` without
+// an expression and `setter(skip)` would call `Default::default()`.
+//
+// Options on the struct that cannot be combined, with `const` or otherwise,
+// are reported at the key that conflicts.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(const)]
+pub struct Proc {
+    #[builder(default)]
+    id: usize,
+    #[builder(setter(skip))]
+    name: &'static str,
+    #[builder(default = "1")]
+    priority: u8,
+}
+
+#[derive(Builder)]
+#[builder(const, validate = "Checked::check")]
+pub struct Checked {
+    id: usize,
+}
+
+#[derive(Builder)]
+#[builder(typestate, async_build)]
+pub struct Loaded {
+    id: usize,
+}
+
+fn main() {}
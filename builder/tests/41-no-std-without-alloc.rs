@@ -0,0 +1,31 @@
+// A builder derived with #[builder(no_std)] only needs `alloc` for what
+// allocates, like the message of a `validate` error. Without any of that its
+// expansion does not refer to `alloc`, so it compiles in a `#![no_std]` crate
+// without `extern crate alloc`.
+//
+// derive_builder itself links `alloc` for `MissingFields`, unless its default
+// `alloc` feature is turned off, which this test cannot do. Running on a host,
+// the test links std anyway, under another name.
+
+#![no_std]
+
+extern crate std as host;
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(no_std)]
+pub struct Uart {
+    #[builder(default = "115200")]
+    baud: u32,
+    parity: bool,
+}
+
+fn main() {
+    let uart = Uart::builder().parity(true).build().unwrap();
+    assert_eq!(uart.baud, 115200);
+    assert!(uart.parity);
+
+    let err = Uart::builder().build().err().unwrap();
+    assert_eq!(err, UartBuilderError::MissingParity);
+}
@@ -0,0 +1,25 @@
+// Each required field and each sub-builder gets a variant in the builder's
+// error enum, named after the field in CamelCase. Two fields whose names only
+// differ in underscores or a raw prefix would get the same variant, which the
+// derive reports rather than generating an enum that does not compile:
+//
+//     error: fields `type` and `type_` would both be reported as `MissingType` in the builder's error
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Server {
+    host: String,
+}
+
+#[derive(Builder)]
+pub struct Record {
+    r#type: String,
+    type_: String,
+    #[builder(sub_builder)]
+    server: Server,
+    #[builder(sub_builder)]
+    server_: Server,
+}
+
+fn main() {}
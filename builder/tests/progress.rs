@@ -1,13 +1,47 @@
 #[test]
 fn tests() {
     let t = trybuild::TestCases::new();
-    //t.pass("tests/01-parse.rs");
-    //t.pass("tests/02-create-builder.rs");
-    //t.pass("tests/03-call-setters.rs");
-    //t.pass("tests/04-call-build.rs");
-    //t.pass("tests/05-method-chaining.rs");
-    //t.pass("tests/06-optional-field.rs");
-    //t.pass("tests/07-repeated-field.rs");
-    //t.compile_fail("tests/08-unrecognized-attribute.rs");
-    //t.pass("tests/09-redefined-prelude-types.rs");
+    t.pass("tests/01-parse.rs");
+    t.pass("tests/02-create-builder.rs");
+    t.pass("tests/03-call-setters.rs");
+    t.pass("tests/04-call-build.rs");
+    t.pass("tests/05-method-chaining.rs");
+    t.pass("tests/06-optional-field.rs");
+    t.pass("tests/07-repeated-field.rs");
+    t.compile_fail("tests/08-unrecognized-attribute.rs");
+    t.pass("tests/09-redefined-prelude-types.rs");
+    t.pass("tests/10-typestate.rs");
+    t.compile_fail("tests/11-typestate-missing-field.rs");
+    t.pass("tests/12-default-value.rs");
+    t.pass("tests/13-error-enum.rs");
+    t.pass("tests/14-validate.rs");
+    t.pass("tests/15-generics.rs");
+    t.pass("tests/16-setter-into.rs");
+    t.pass("tests/17-builder-pattern.rs");
+    t.compile_fail("tests/18-invalid-pattern.rs");
+    t.pass("tests/19-enum-variants.rs");
+    t.pass("tests/20-tuple-struct.rs");
+    t.pass("tests/21-collections.rs");
+    t.pass("tests/22-sub-builder.rs");
+    #[cfg(feature = "serde")]
+    t.pass("tests/23-serde.rs");
+    t.pass("tests/24-merge.rs");
+    t.pass("tests/25-env.rs");
+    t.pass("tests/26-setter-options.rs");
+    t.compile_fail("tests/27-private-setter.rs");
+    t.compile_fail("tests/28-attribute-errors.rs");
+    t.pass("tests/29-no-std.rs");
+    t.pass("tests/30-buildable.rs");
+    t.pass("tests/31-from-value.rs");
+    t.pass("tests/32-fallible-defaults.rs");
+    t.pass("tests/33-const-builder.rs");
+    t.compile_fail("tests/34-const-missing-field.rs");
+    t.pass("tests/35-docs.rs");
+    t.pass("tests/36-missing-fields.rs");
+    t.pass("tests/37-in-place-setters.rs");
+    t.pass("tests/38-raw-identifiers.rs");
+    t.compile_fail("tests/39-variant-attribute-errors.rs");
+    t.compile_fail("tests/40-const-unsupported.rs");
+    t.pass("tests/41-no-std-without-alloc.rs");
+    t.compile_fail("tests/42-error-variant-clash.rs");
 }
@@ -0,0 +1,53 @@
+use syn::punctuated::Punctuated;
+use syn::{Attribute, Expr, ExprLit, Lit, LitStr, Meta, Token, WherePredicate};
+
+/// The `#[debug(bound = "...")]` attribute on the struct or enum, or on one of
+/// its fields.
+pub fn bound(attrs: &[Attribute]) -> syn::Result<Option<Vec<WherePredicate>>> {
+    let mut bound = None;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("debug")) {
+        let Meta::List(_) = &attr.meta else {
+            continue;
+        };
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("bound") {
+                let lit: LitStr = meta.value()?.parse()?;
+                let predicates =
+                    lit.parse_with(Punctuated::<WherePredicate, Token![,]>::parse_terminated)?;
+                bound = Some(predicates.into_iter().collect());
+                Ok(())
+            } else {
+                Err(meta.error("expected `debug(bound = \"...\")`"))
+            }
+        })?;
+    }
+    Ok(bound)
+}
+
+/// The `#[debug = "..."]` format string of a field.
+pub fn format(attrs: &[Attribute]) -> syn::Result<Option<LitStr>> {
+    let mut format = None;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("debug")) {
+        match &attr.meta {
+            Meta::NameValue(meta) => match &meta.value {
+                Expr::Lit(ExprLit {
+                    lit: Lit::Str(lit), ..
+                }) => format = Some(lit.clone()),
+                value => {
+                    return Err(syn::Error::new_spanned(
+                        value,
+                        "expected a format string, as in `debug = \"0b{:08b}\"`",
+                    ))
+                }
+            },
+            Meta::List(_) => {}
+            Meta::Path(path) => {
+                return Err(syn::Error::new_spanned(
+                    path,
+                    "expected `debug = \"...\"` or `debug(bound = \"...\")`",
+                ))
+            }
+        }
+    }
+    Ok(format)
+}
@@ -0,0 +1,81 @@
+use quote::ToTokens;
+use std::collections::BTreeSet;
+use syn::visit::{self, Visit};
+use syn::{parse_quote, Generics, Ident, Type, TypePath, WherePredicate};
+
+/// Where-clause predicates inferred from the types of the fields that are
+/// formatted: `T: Debug` for a type parameter used directly, and
+/// `T::Value: Debug` for an associated type of one. Parameters that only
+/// appear inside `PhantomData` are left unbounded, as is every type that does
+/// not mention a type parameter.
+pub struct Inferred<'a> {
+    params: BTreeSet<&'a Ident>,
+    predicates: Vec<WherePredicate>,
+    seen: BTreeSet<String>,
+}
+
+impl<'a> Inferred<'a> {
+    pub fn new(generics: &'a Generics) -> Self {
+        Inferred {
+            params: generics.type_params().map(|param| &param.ident).collect(),
+            predicates: Vec::new(),
+            seen: BTreeSet::new(),
+        }
+    }
+
+    pub fn add_type(&mut self, ty: &Type) {
+        let mut visitor = Visitor {
+            params: &self.params,
+            bounded: Vec::new(),
+        };
+        visitor.visit_type(ty);
+        let predicates = visitor
+            .bounded
+            .into_iter()
+            .map(|bounded| parse_quote!(#bounded: ::std::fmt::Debug))
+            .collect::<Vec<_>>();
+        predicates.into_iter().for_each(|p| self.push(p));
+    }
+
+    pub fn push(&mut self, predicate: WherePredicate) {
+        // Several fields often need the same bound.
+        if self.seen.insert(predicate.to_token_stream().to_string()) {
+            self.predicates.push(predicate);
+        }
+    }
+
+    pub fn finish(self, generics: &Generics) -> Generics {
+        let mut generics = generics.clone();
+        generics
+            .make_where_clause()
+            .predicates
+            .extend(self.predicates);
+        generics
+    }
+}
+
+struct Visitor<'a, 'p> {
+    params: &'a BTreeSet<&'p Ident>,
+    bounded: Vec<&'a TypePath>,
+}
+
+impl<'a> Visit<'a> for Visitor<'a, '_> {
+    fn visit_type_path(&mut self, ty: &'a TypePath) {
+        let segments = &ty.path.segments;
+        if ty.qself.is_none() && ty.path.leading_colon.is_none() {
+            if let Some(first) = segments.first() {
+                if self.params.contains(&first.ident) {
+                    self.bounded.push(ty);
+                    return;
+                }
+            }
+        }
+        if segments
+            .last()
+            .is_some_and(|last| last.ident == "PhantomData")
+        {
+            return;
+        }
+        visit::visit_type_path(self, ty);
+    }
+}
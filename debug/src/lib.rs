@@ -1,8 +1,138 @@
+mod attr;
+mod bound;
+
+use crate::bound::Inferred;
 use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::ext::IdentExt;
+use syn::{parse_macro_input, Data, DeriveInput, Fields};
 
-#[proc_macro_derive(CustomDebug)]
+#[proc_macro_derive(CustomDebug, attributes(debug))]
 pub fn derive(input: TokenStream) -> TokenStream {
-    let _ = input;
+    let input = parse_macro_input!(input as DeriveInput);
+
+    expand(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let ident = &input.ident;
+    let mut inferred = Inferred::new(&input.generics);
+
+    let body = match &input.data {
+        Data::Struct(data) => {
+            infer(&mut inferred, &data.fields)?;
+            let (pattern, body) = fmt_fields(&ident.unraw().to_string(), &data.fields)?;
+            quote! {
+                let #ident #pattern = self;
+                #body
+            }
+        }
+        Data::Enum(data) => {
+            let arms = data
+                .variants
+                .iter()
+                .map(|variant| {
+                    infer(&mut inferred, &variant.fields)?;
+                    let variant_ident = &variant.ident;
+                    let (pattern, body) =
+                        fmt_fields(&variant_ident.unraw().to_string(), &variant.fields)?;
+                    Ok(quote!(#ident::#variant_ident #pattern => #body,))
+                })
+                .collect::<syn::Result<TokenStream2>>()?;
+            // A reference to an empty enum is not itself uninhabited.
+            let scrutinee = if data.variants.is_empty() {
+                quote!(*self)
+            } else {
+                quote!(self)
+            };
+            quote! {
+                match #scrutinee {
+                    #arms
+                }
+            }
+        }
+        Data::Union(_) => {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "CustomDebug can only be derived for structs and enums",
+            ))
+        }
+    };
+
+    // A bound on the type replaces every inferred one.
+    let generics = match attr::bound(&input.attrs)? {
+        Some(predicates) => {
+            let mut generics = input.generics.clone();
+            generics.make_where_clause().predicates.extend(predicates);
+            generics
+        }
+        None => inferred.finish(&input.generics),
+    };
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::std::fmt::Debug for #ident #ty_generics #where_clause {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                #body
+            }
+        }
+    })
+}
+
+/// Infers the bounds needed to format the fields, unless a field has its own
+/// `#[debug(bound = "...")]`.
+fn infer(inferred: &mut Inferred, fields: &Fields) -> syn::Result<()> {
+    for field in fields {
+        match attr::bound(&field.attrs)? {
+            Some(predicates) => predicates.into_iter().for_each(|p| inferred.push(p)),
+            None => inferred.add_type(&field.ty),
+        }
+    }
+    Ok(())
+}
+
+/// The pattern binding every field of a struct or variant, and the expression
+/// formatting them the way `#[derive(Debug)]` does.
+fn fmt_fields(name: &str, fields: &Fields) -> syn::Result<(TokenStream2, TokenStream2)> {
+    // Fields are bound to fresh names, as one could be called `f`.
+    let bindings = (0..fields.len())
+        .map(|i| format_ident!("__field{}", i))
+        .collect::<Vec<_>>();
+    let values = fields
+        .iter()
+        .zip(&bindings)
+        .map(|(field, binding)| {
+            Ok(match attr::format(&field.attrs)? {
+                Some(format) => quote!(&::std::format_args!(#format, #binding)),
+                None => quote!(#binding),
+            })
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
 
-    unimplemented!()
+    Ok(match fields {
+        Fields::Named(_) => {
+            let idents = fields.iter().map(|f| f.ident.as_ref().unwrap());
+            let names = idents.clone().map(|ident| ident.unraw().to_string());
+            (
+                quote!({ #(#idents: #bindings),* }),
+                quote! {
+                    f.debug_struct(#name)
+                        #(.field(#names, #values))*
+                        .finish()
+                },
+            )
+        }
+        Fields::Unnamed(_) => (
+            quote!((#(#bindings),*)),
+            quote! {
+                f.debug_tuple(#name)
+                    #(.field(#values))*
+                    .finish()
+            },
+        ),
+        Fields::Unit => (quote!(), quote!(f.write_str(#name))),
+    })
 }
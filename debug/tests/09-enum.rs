@@ -0,0 +1,91 @@
+// Enums derive CustomDebug too. Each variant is formatted the way
+// #[derive(Debug)] would format it: a unit variant as its name, a tuple
+// variant with debug_tuple and a struct variant with debug_struct.
+//
+//     Event::Quit                      =>  Quit
+//     Event::Key('q', 7)               =>  Key('q', 0b00000111)
+//     Event::Move { x: 1, y: 2 }       =>  Move { x: 1, y: 2 }
+//
+// The #[debug = "..."] attribute works on the fields of variants like on the
+// fields of a struct, and bounds are inferred from the field types of every
+// variant, so `T: Debug` is required for Tree<T> but no bound is added for the
+// parameter of Marker<T>.
+//
+// Raw identifiers are printed without their `r#` prefix, as #[derive(Debug)]
+// does: `r#Move { r#loop: 1 }` is formatted as `Move { loop: 1 }`.
+
+use derive_debug::CustomDebug;
+use std::fmt::Debug;
+use std::marker::PhantomData;
+
+#[derive(CustomDebug)]
+pub enum Event {
+    Quit,
+    Key(char, #[debug = "0b{:08b}"] u8),
+    Move { x: i32, y: i32 },
+    Resize {},
+}
+
+#[derive(Debug)]
+pub enum Expected {
+    Quit,
+    Key(char, u8),
+    Move { x: i32, y: i32 },
+    Resize {},
+}
+
+#[derive(CustomDebug)]
+pub enum Tree<T> {
+    Leaf(T),
+    Node { left: Box<Tree<T>>, right: Box<Tree<T>> },
+}
+
+#[derive(CustomDebug)]
+pub enum Marker<T> {
+    Empty(PhantomData<T>),
+}
+
+#[derive(CustomDebug)]
+pub enum Never {}
+
+#[derive(CustomDebug)]
+pub enum r#Raw {
+    r#Move { r#loop: u8 },
+    r#Match(u8),
+}
+
+fn assert_debug<F: Debug>() {}
+
+fn main() {
+    struct NotDebug;
+
+    assert_eq!(format!("{:?}", Event::Quit), format!("{:?}", Expected::Quit));
+    assert_eq!(
+        format!("{:?}", Event::Move { x: 1, y: 2 }),
+        format!("{:?}", Expected::Move { x: 1, y: 2 }),
+    );
+    assert_eq!(
+        format!("{:#?}", Event::Move { x: 1, y: 2 }),
+        format!("{:#?}", Expected::Move { x: 1, y: 2 }),
+    );
+    assert_eq!(
+        format!("{:?}", Event::Resize {}),
+        format!("{:?}", Expected::Resize {}),
+    );
+    assert_eq!(format!("{:?}", Event::Key('q', 7)), "Key('q', 0b00000111)");
+
+    let tree = Tree::Node {
+        left: Box::new(Tree::Leaf(1)),
+        right: Box::new(Tree::Leaf(2)),
+    };
+    assert_eq!(
+        format!("{:?}", tree),
+        "Node { left: Leaf(1), right: Leaf(2) }",
+    );
+
+    assert_eq!(format!("{:?}", Raw::Move { r#loop: 1 }), "Move { loop: 1 }");
+    assert_eq!(format!("{:?}", r#Raw::r#Match(2)), "Match(2)");
+
+    assert_debug::<Marker<NotDebug>>();
+    assert_debug::<Never>();
+}